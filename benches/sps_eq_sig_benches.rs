@@ -3,24 +3,27 @@ use rand::thread_rng;
 #[macro_use]
 extern crate criterion;
 
-use criterion::Criterion;
+use criterion::{BenchmarkId, Criterion};
 
 use sps_eq::sign::*;
 use sps_eq::verify::*;
 
-use ark_bls12_381::{Bls12_381, G1Projective as G1};
+use ark_bls12_381::{Bls12_381, G1Projective as G1, G2Projective as G2};
 
 mod proof_of_credential_benches {
     use super::*;
+    use ark_ec::{PairingEngine, ProjectiveCurve};
     use ark_ff::UniformRand;
 
+    const CAPACITIES: [usize; 5] = [2, 4, 8, 16, 32];
+
     fn signature(c: &mut Criterion) {
         let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
 
-        let message = vec![G1::rand(&mut thread_rng()); 2];
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
 
         c.bench_function("Signature", |b| {
-            b.iter(|| sk.sign(&message, &mut thread_rng()));
+            b.iter(|| sk.sign(&message, &mut thread_rng()).unwrap());
         });
     }
 
@@ -28,20 +31,136 @@ mod proof_of_credential_benches {
         let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
         let pk = PublicKey::from(&sk);
 
-        let message = vec![G1::rand(&mut thread_rng()); 2];
-        let signature = sk.sign(&message, &mut thread_rng());
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+        let signature = sk.sign(&message, &mut thread_rng()).unwrap();
 
-        c.bench_function("Signature", |b| {
+        c.bench_function("Verification", |b| {
             b.iter(|| pk.verify(&message, &signature));
         });
     }
 
+    fn signature_per_capacity(c: &mut Criterion) {
+        let mut group = c.benchmark_group("Signature per capacity");
+
+        for &capacity in CAPACITIES.iter() {
+            let sk = SigningKey::<Bls12_381>::new(capacity, &mut thread_rng());
+            let message: Vec<G1> = (0..capacity).map(|_| G1::rand(&mut thread_rng())).collect();
+
+            group.bench_with_input(BenchmarkId::from_parameter(capacity), &capacity, |b, _| {
+                b.iter(|| sk.sign(&message, &mut thread_rng()).unwrap())
+            });
+        }
+
+        group.finish();
+    }
+
+    fn key_derivation_per_capacity(c: &mut Criterion) {
+        let mut group = c.benchmark_group("Public key derivation per capacity");
+
+        for &capacity in CAPACITIES.iter() {
+            let sk = SigningKey::<Bls12_381>::new(capacity, &mut thread_rng());
+
+            group.bench_with_input(BenchmarkId::from_parameter(capacity), &capacity, |b, _| {
+                b.iter(|| PublicKey::from(&sk))
+            });
+        }
+
+        group.finish();
+    }
+
+    /// Verification computing each of the l + 3 pairings separately, each with its own final
+    /// exponentiation. Used as a baseline for `PublicKey::verify`.
+    fn verify_with_separate_pairings(
+        pk: &PublicKey<Bls12_381>,
+        message: &[G1],
+        signature: &SpsEqSignature<Bls12_381>,
+    ) -> bool {
+        let mut check_1 = Bls12_381::pairing(message[0], pk.into_iter().next().unwrap());
+        for (&message, key) in message.iter().zip(pk).skip(1) {
+            check_1 *= &Bls12_381::pairing(message, key);
+        }
+        let check_2 = Bls12_381::pairing(signature.Y, G2::prime_subgroup_generator());
+
+        check_1 == Bls12_381::pairing(signature.Z, signature.Yp)
+            && check_2 == Bls12_381::pairing(G1::prime_subgroup_generator(), signature.Yp)
+    }
+
+    fn verification_per_capacity(c: &mut Criterion) {
+        let mut group = c.benchmark_group("Verification per capacity");
+
+        for &capacity in CAPACITIES.iter() {
+            let sk = SigningKey::<Bls12_381>::new(capacity, &mut thread_rng());
+            let pk = PublicKey::from(&sk);
+
+            let message: Vec<G1> = (0..capacity).map(|_| G1::rand(&mut thread_rng())).collect();
+            let signature = sk.sign(&message, &mut thread_rng()).unwrap();
+
+            group.bench_with_input(
+                BenchmarkId::new("Product of pairings", capacity),
+                &capacity,
+                |b, _| b.iter(|| pk.verify(&message, &signature)),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("Separate pairings", capacity),
+                &capacity,
+                |b, _| b.iter(|| verify_with_separate_pairings(&pk, &message, &signature)),
+            );
+        }
+
+        group.finish();
+    }
+
+    fn batch_verification(c: &mut Criterion) {
+        let mut group = c.benchmark_group("Batch verification");
+
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+        let pk = PublicKey::from(&sk);
+
+        for &batch_size in [16, 64].iter() {
+            let messages: Vec<Vec<G1>> = (0..batch_size)
+                .map(|_| (0..2).map(|_| G1::rand(&mut thread_rng())).collect())
+                .collect();
+            let signatures: Vec<_> = messages
+                .iter()
+                .map(|message| sk.sign(message, &mut thread_rng()).unwrap())
+                .collect();
+            let batch: Vec<_> = messages
+                .iter()
+                .map(Vec::as_slice)
+                .zip(&signatures)
+                .collect();
+
+            group.bench_with_input(
+                BenchmarkId::new("Batch", batch_size),
+                &batch_size,
+                |b, _| b.iter(|| pk.batch_verify(&batch, &mut thread_rng())),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("One by one", batch_size),
+                &batch_size,
+                |b, _| {
+                    b.iter(|| {
+                        batch
+                            .iter()
+                            .all(|(message, signature)| pk.verify(message, signature).is_ok())
+                    })
+                },
+            );
+        }
+
+        group.finish();
+    }
+
     criterion_group! {
         name = signature_benches;
         config = Criterion::default();
         targets =
             signature,
+            signature_per_capacity,
+            key_derivation_per_capacity,
             verification,
+            verification_per_capacity,
+            batch_verification,
     }
 }
 
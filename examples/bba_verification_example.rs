@@ -7,8 +7,10 @@
 use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
 use ark_ec::ProjectiveCurve;
 use ark_ff::{UniformRand, Zero};
+use merlin::Transcript;
 use rand::thread_rng;
 
+use sps_eq::proofs::InnerProductProof;
 use sps_eq::sign::*;
 use sps_eq::verify::*;
 
@@ -16,7 +18,9 @@ fn main() {
     let number_attributes = 5usize;
     let number_counters = number_attributes - 2;
     // Key pair of issuer for the state of the token
-    let sk_issuer = vec![Fr::rand(&mut thread_rng()); number_attributes];
+    let sk_issuer: Vec<Fr> = (0..number_attributes)
+        .map(|_| Fr::rand(&mut thread_rng()))
+        .collect();
     let mut pk_issuer = vec![G1::prime_subgroup_generator(); number_attributes];
     for (base, sk) in pk_issuer.iter_mut().zip(sk_issuer.iter()) {
         *base *= *sk;
@@ -31,14 +35,18 @@ fn main() {
     let user_randomness = Fr::rand(&mut thread_rng());
 
     // policy vector used in the example
-    let policy_vector = vec![Fr::rand(&mut thread_rng()); number_counters];
+    let policy_vector: Vec<Fr> = (0..number_counters)
+        .map(|_| Fr::rand(&mut thread_rng()))
+        .collect();
 
     // for this example we consider that the state of the BBA is already
     // updated.
-    let state = vec![Fr::rand(&mut thread_rng()); number_counters];
+    let state: Vec<Fr> = (0..number_counters)
+        .map(|_| Fr::rand(&mut thread_rng()))
+        .collect();
     let mut token_opening = state.clone();
-    token_opening.push(token_identifier.into());
-    token_opening.push(user_randomness.into());
+    token_opening.push(token_identifier);
+    token_opening.push(user_randomness);
 
     let mut token_commitment = G1::zero();
     for (pk, opening) in pk_issuer.iter().zip(token_opening.iter()) {
@@ -50,35 +58,44 @@ fn main() {
     // the token itself is created by the equivalence class of [token_commitment, generator]
     let token = vec![token_commitment, G1::prime_subgroup_generator()];
     // this equivalence class will be signed by the issuer.
-    let signature = sk_sps.sign(&token, &mut thread_rng());
+    let signature = sk_sps.sign(&token, &mut thread_rng()).unwrap();
 
-    // For sake of simplicity we abstract the zero knowledge proof in this example. The
-    // user, to prove ownership of the token will disclose all its openings. In reality
-    // the user hides its randomness and the actual state of the counters. It directly
-    // computes the zero knowledge proof, and simply proves correctness of the computation
-    let mut reward = Fr::zero();
-    for (state, policy) in token_opening.iter().zip(policy_vector.iter()) {
-        reward += *state * *policy;
-    }
+    // To show the token, the user moves it to a fresh representative of its equivalence
+    // class. Instead of disclosing the opening, it proves knowledge of it, together with the
+    // inner product of the state of the counters with the policy, which is the reward.
+    let (show_signature, show_token, (f, _)) =
+        signature.generate_new_repr_with_randomness(&token, &mut thread_rng());
+    let (reward_proof, reward) = InnerProductProof::<G1>::prove(
+        &mut Transcript::new(b"bba-show"),
+        &pk_issuer,
+        &show_token,
+        &token_opening,
+        f,
+        &policy_vector,
+        &mut thread_rng(),
+    )
+    .unwrap();
 
-    let proof = (token_opening, token, signature, reward);
+    let proof = (show_token, show_signature, reward_proof, reward);
 
-    // The verification procedure will verify the proof, rather than computing the actual
-    // inner product. Similarly, it will verify the proof of opening knowledge, rather than
-    // receive the opening itself.
-    let mut verif_token = G1::zero();
-    for (opening, pk) in proof.0.iter().zip(pk_issuer.iter()) {
-        let mut temp = *pk;
-        temp *= *opening;
-        verif_token += temp;
-    }
+    // The verification procedure verifies the signature over the new representative of the
+    // token, and the proof of the reward, without learning the state of the counters.
+    assert!(pk_sps.verify(&proof.0, &proof.1).is_ok());
+    assert!(proof
+        .2
+        .verify(
+            &mut Transcript::new(b"bba-show"),
+            &pk_issuer,
+            &proof.0,
+            &policy_vector,
+            proof.3
+        )
+        .is_ok());
 
-    let mut verif_reward = Fr::zero();
-    for (state, policy) in proof.0.iter().zip(policy_vector.iter()) {
-        verif_reward += *state * *policy;
+    // Only used here to check the example: the reward matches the state of the counters
+    let mut expected_reward = Fr::zero();
+    for (state, policy) in state.iter().zip(policy_vector.iter()) {
+        expected_reward += *state * *policy;
     }
-
-    assert_eq!(verif_reward, proof.3);
-    assert_eq!(verif_token, proof.1[0]);
-    assert!(pk_sps.verify(&proof.1, &proof.2).is_ok());
+    assert_eq!(expected_reward, proof.3);
 }
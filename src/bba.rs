@@ -0,0 +1,786 @@
+//! Module describing black-box accumulation (BBA+) tokens built on SPS-EQ signatures.
+//!
+//! A token holds a vector of counters, and is a commitment
+//! $C = \sum_{i\in\left[n\right]} c_i B_i + s B_s + u B_u + x B_x$ to the counters $c_i$, a
+//! serial number $s$, a double-spending randomness $u$ and the secret key $x$ of the user, under
+//! the bases of the issuer. The issuer signs the equivalence class of $(C, g_1)$, so that the user
+//! can show the token as an unlinkable representative $(fC, fg_1)$.
+//!
+//! The lifecycle of a token is as follows:
+//!
+//! * Setup: the issuer generates its bases and an SPS-EQ key pair of capacity 2, and publishes
+//!   [`IssuerParameters`]; users generate a [`UserKey`].
+//! * Issuance: the user commits to zero counters, a fresh serial number and its key, and proves
+//!   knowledge of the opening. The issuer learns neither the serial number nor the opening, and
+//!   signs the commitment.
+//! * Accumulate: the user shows the token, discloses its serial number with a double-spending
+//!   tag, and commits to a new token whose counters are incremented by public amounts. The
+//!   issuer checks the proof and signs the new token.
+//! * Redeem: the user shows the token with a double-spending tag, and proves that the inner
+//!   product of the counters with a public policy equals the claimed reward.
+//!
+//! A double-spending tag is $t = x\gamma + u$ for a challenge $\gamma$ of the issuer. The serial
+//! number reveals when a token is shown twice, and two tags with different challenges reveal the
+//! secret key $x$ of the user, see [`identify`].
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use merlin::Transcript;
+use rand::{CryptoRng, Rng};
+
+use crate::errors::*;
+use crate::proofs::{LinearProof, LinearRelation};
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
+
+/// Public parameters of a token issuer
+#[derive(Debug)]
+pub struct IssuerParameters<E: PairingEngine> {
+    /// Number of counters of a token
+    pub counters: usize,
+    /// Bases of the commitments: one per counter, then the serial number, the double-spending
+    /// randomness and the user key
+    bases: Vec<E::G1Projective>,
+    /// Key verifying the signatures over tokens
+    pub public_key: PublicKey<E>,
+}
+
+/// Token issuer, holding the signing key
+#[derive(Debug)]
+pub struct Issuer<E: PairingEngine> {
+    signing_key: SigningKey<E>,
+    /// Public parameters of the issuer
+    pub parameters: IssuerParameters<E>,
+}
+
+/// Key pair of a user
+#[derive(Clone, Debug)]
+pub struct UserKey<E: PairingEngine> {
+    secret_key: E::Fr,
+    /// Public key of the user, `x g1`
+    pub public_key: E::G1Projective,
+}
+
+/// Token held by a user
+#[derive(Clone, Debug)]
+pub struct Token<E: PairingEngine> {
+    counters: Vec<E::Fr>,
+    serial: E::Fr,
+    ds_randomness: E::Fr,
+    message: Vec<E::G1Projective>,
+    signature: SpsEqSignature<E>,
+}
+
+/// Token requested by a user and not yet signed by the issuer
+#[derive(Clone, Debug)]
+pub struct PendingToken<E: PairingEngine> {
+    counters: Vec<E::Fr>,
+    serial: E::Fr,
+    ds_randomness: E::Fr,
+    commitment: E::G1Projective,
+}
+
+/// Request for the issuance of a new token
+#[derive(Clone, Debug)]
+pub struct IssuanceRequest<E: PairingEngine> {
+    /// Commitment to the new token
+    pub commitment: E::G1Projective,
+    /// Public key of the user
+    pub user_public_key: E::G1Projective,
+    proof: LinearProof<E::G1Projective>,
+}
+
+/// Double-spending tag disclosed when showing a token
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleSpendTag<E: PairingEngine> {
+    /// Serial number of the shown token
+    pub serial: E::Fr,
+    /// Challenge of the issuer
+    pub challenge: E::Fr,
+    /// Tag `x challenge + u`
+    pub tag: E::Fr,
+}
+
+/// Showing of a token: a new representative of the token, its signature and a double-spending
+/// tag
+#[derive(Clone, Debug)]
+pub struct TokenShow<E: PairingEngine> {
+    /// Representative of the token
+    pub message: Vec<E::G1Projective>,
+    /// Signature over the representative
+    pub signature: SpsEqSignature<E>,
+    /// Double-spending tag
+    pub double_spend_tag: DoubleSpendTag<E>,
+}
+
+/// Request to accumulate increments into a token
+#[derive(Clone, Debug)]
+pub struct AccumulateRequest<E: PairingEngine> {
+    /// Showing of the current token
+    pub show: TokenShow<E>,
+    /// Commitment to the updated token
+    pub commitment: E::G1Projective,
+    proof: LinearProof<E::G1Projective>,
+}
+
+/// Request to redeem a token
+#[derive(Clone, Debug)]
+pub struct RedeemRequest<E: PairingEngine> {
+    /// Showing of the token
+    pub show: TokenShow<E>,
+    /// Inner product of the counters with the policy
+    pub reward: E::Fr,
+    proof: LinearProof<E::G1Projective>,
+}
+
+/// Serial numbers of the tokens shown so far, with their double-spending tags
+#[derive(Clone, Debug)]
+pub struct SpentTokens<E: PairingEngine> {
+    tags: BTreeMap<E::Fr, DoubleSpendTag<E>>,
+}
+
+impl<E: PairingEngine> IssuerParameters<E> {
+    fn counter_bases(&self) -> &[E::G1Projective] {
+        &self.bases[..self.counters]
+    }
+
+    fn serial_base(&self) -> E::G1Projective {
+        self.bases[self.counters]
+    }
+
+    fn ds_randomness_base(&self) -> E::G1Projective {
+        self.bases[self.counters + 1]
+    }
+
+    fn user_key_base(&self) -> E::G1Projective {
+        self.bases[self.counters + 2]
+    }
+
+    fn commit(
+        &self,
+        counters: &[E::Fr],
+        serial: E::Fr,
+        ds_randomness: E::Fr,
+        key: E::Fr,
+    ) -> E::G1Projective {
+        counters
+            .iter()
+            .chain(&[serial, ds_randomness, key])
+            .zip(&self.bases)
+            .map(|(value, base)| base.mul(value.into_repr()))
+            .sum()
+    }
+
+    /// Relation of a showing with `extra_witness` additional witness components, over the
+    /// witness `(1/f, c_1, ..., c_n, u, x, ...)`
+    fn show_relation(
+        &self,
+        show: &TokenShow<E>,
+        extra_witness: usize,
+    ) -> Result<LinearRelation<E::G1Projective>, SpsEqSignatureError> {
+        if show.message.len() != 2 {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let n = self.counters;
+        let generator = E::G1Projective::prime_subgroup_generator();
+        let tag = &show.double_spend_tag;
+        let mut relation = LinearRelation::new(n + 3 + extra_witness);
+
+        // (1/f) f g1 = g1
+        relation.add_equation(vec![(0, show.message[1])], generator)?;
+        // (1/f) C' - sum_i c_i B_i - u B_u - x B_x = s B_s
+        let mut terms = vec![(0, show.message[0])];
+        terms.extend(
+            self.counter_bases()
+                .iter()
+                .enumerate()
+                .map(|(index, base)| (index + 1, -*base)),
+        );
+        terms.push((n + 1, -self.ds_randomness_base()));
+        terms.push((n + 2, -self.user_key_base()));
+        relation.add_equation(terms, self.serial_base().mul(tag.serial.into_repr()))?;
+        // x challenge g1 + u g1 = t g1
+        relation.add_equation(
+            vec![
+                (n + 2, generator.mul(tag.challenge.into_repr())),
+                (n + 1, generator),
+            ],
+            generator.mul(tag.tag.into_repr()),
+        )?;
+
+        Ok(relation)
+    }
+
+    /// Adds to a showing relation the equation of the commitment to the updated token, with
+    /// the fresh serial number and double-spending randomness at `n + 3` and `n + 4`
+    fn add_update_equation(
+        &self,
+        relation: &mut LinearRelation<E::G1Projective>,
+        commitment: E::G1Projective,
+        increments: &[E::Fr],
+    ) -> Result<(), SpsEqSignatureError> {
+        if increments.len() != self.counters {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let n = self.counters;
+        let mut terms: Vec<(usize, E::G1Projective)> = self
+            .counter_bases()
+            .iter()
+            .enumerate()
+            .map(|(index, base)| (index + 1, *base))
+            .collect();
+        terms.push((n + 3, self.serial_base()));
+        terms.push((n + 4, self.ds_randomness_base()));
+        terms.push((n + 2, self.user_key_base()));
+
+        let increment: E::G1Projective = increments
+            .iter()
+            .zip(self.counter_bases())
+            .map(|(value, base)| base.mul(value.into_repr()))
+            .sum();
+        relation.add_equation(terms, commitment - increment)
+    }
+
+    fn issuance_relation(
+        &self,
+        commitment: E::G1Projective,
+        user_public_key: E::G1Projective,
+    ) -> Result<LinearRelation<E::G1Projective>, SpsEqSignatureError> {
+        let mut relation = LinearRelation::new(3);
+        relation.add_equation(
+            vec![
+                (0, self.serial_base()),
+                (1, self.ds_randomness_base()),
+                (2, self.user_key_base()),
+            ],
+            commitment,
+        )?;
+        relation.add_equation(
+            vec![(2, E::G1Projective::prime_subgroup_generator())],
+            user_public_key,
+        )?;
+        Ok(relation)
+    }
+
+    fn redeem_relation(
+        &self,
+        show: &TokenShow<E>,
+        policy: &[E::Fr],
+        reward: E::Fr,
+    ) -> Result<LinearRelation<E::G1Projective>, SpsEqSignatureError> {
+        if policy.len() != self.counters {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let mut relation = self.show_relation(show, 0)?;
+        let generator = E::G1Projective::prime_subgroup_generator();
+        let terms = policy
+            .iter()
+            .enumerate()
+            .map(|(index, weight)| (index + 1, generator.mul(weight.into_repr())))
+            .collect();
+        relation.add_equation(terms, generator.mul(reward.into_repr()))?;
+        Ok(relation)
+    }
+}
+
+impl<E: PairingEngine> Issuer<E> {
+    /// Sets up an issuer of tokens with `counters` counters
+    pub fn new<R>(counters: usize, rng: &mut R) -> Self
+    where
+        R: Rng + CryptoRng,
+    {
+        let signing_key = SigningKey::new(2, rng);
+        let bases = (0..counters + 3)
+            .map(|_| E::G1Projective::rand(rng))
+            .collect();
+        let parameters = IssuerParameters {
+            counters,
+            bases,
+            public_key: PublicKey::from(&signing_key),
+        };
+
+        Issuer {
+            signing_key,
+            parameters,
+        }
+    }
+
+    /// Checks an issuance request and signs the new token
+    pub fn issue<R>(
+        &self,
+        request: &IssuanceRequest<E>,
+        rng: &mut R,
+    ) -> Result<SpsEqSignature<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        self.parameters
+            .issuance_relation(request.commitment, request.user_public_key)?
+            .verify(&mut Transcript::new(b"bba-issue"), &request.proof)?;
+        self.sign_token(request.commitment, rng)
+    }
+
+    /// Checks an accumulate request for the given increments and challenge, records its
+    /// double-spending tag, and signs the updated token
+    pub fn accumulate<R>(
+        &self,
+        request: &AccumulateRequest<E>,
+        increments: &[E::Fr],
+        challenge: E::Fr,
+        spent_tokens: &mut SpentTokens<E>,
+        rng: &mut R,
+    ) -> Result<SpsEqSignature<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        self.check_show(&request.show, challenge)?;
+        let mut relation = self.parameters.show_relation(&request.show, 2)?;
+        self.parameters
+            .add_update_equation(&mut relation, request.commitment, increments)?;
+        relation.verify(&mut Transcript::new(b"bba-accumulate"), &request.proof)?;
+
+        spent_tokens.insert(&request.show.double_spend_tag)?;
+        self.sign_token(request.commitment, rng)
+    }
+
+    /// Checks a redeem request for the given policy and challenge, and records its
+    /// double-spending tag. On success, the reward of the request is the inner product of the
+    /// counters of the token with the policy.
+    pub fn redeem(
+        &self,
+        request: &RedeemRequest<E>,
+        policy: &[E::Fr],
+        challenge: E::Fr,
+        spent_tokens: &mut SpentTokens<E>,
+    ) -> Result<(), SpsEqSignatureError> {
+        self.check_show(&request.show, challenge)?;
+        self.parameters
+            .redeem_relation(&request.show, policy, request.reward)?
+            .verify(&mut Transcript::new(b"bba-redeem"), &request.proof)?;
+
+        spent_tokens.insert(&request.show.double_spend_tag)
+    }
+
+    /// Checks that a showing answers the challenge and carries a valid signature
+    fn check_show(&self, show: &TokenShow<E>, challenge: E::Fr) -> Result<(), SpsEqSignatureError> {
+        if show.double_spend_tag.challenge != challenge {
+            return Err(SpsEqSignatureError::InvalidProof);
+        }
+        self.parameters
+            .public_key
+            .verify(&show.message, &show.signature)
+    }
+
+    fn sign_token<R>(
+        &self,
+        commitment: E::G1Projective,
+        rng: &mut R,
+    ) -> Result<SpsEqSignature<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        self.signing_key.sign(
+            &[commitment, E::G1Projective::prime_subgroup_generator()],
+            rng,
+        )
+    }
+}
+
+impl<E: PairingEngine> UserKey<E> {
+    /// Generates a key pair for a user
+    pub fn new<R>(rng: &mut R) -> Self
+    where
+        R: Rng + CryptoRng,
+    {
+        let secret_key: E::Fr = random_nonzero_scalar(rng);
+        UserKey {
+            secret_key,
+            public_key: E::G1Projective::prime_subgroup_generator().mul(secret_key.into_repr()),
+        }
+    }
+
+    /// Requests a new token with all counters set to zero
+    pub fn request_issuance<R>(
+        &self,
+        parameters: &IssuerParameters<E>,
+        rng: &mut R,
+    ) -> Result<(IssuanceRequest<E>, PendingToken<E>), SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        let counters = vec![E::Fr::zero(); parameters.counters];
+        let pending = PendingToken::new(parameters, counters, self, rng);
+
+        let proof = parameters
+            .issuance_relation(pending.commitment, self.public_key)?
+            .prove(
+                &mut Transcript::new(b"bba-issue"),
+                &[pending.serial, pending.ds_randomness, self.secret_key],
+                rng,
+            )?;
+
+        let request = IssuanceRequest {
+            commitment: pending.commitment,
+            user_public_key: self.public_key,
+            proof,
+        };
+        Ok((request, pending))
+    }
+}
+
+impl<E: PairingEngine> PendingToken<E> {
+    fn new<R>(
+        parameters: &IssuerParameters<E>,
+        counters: Vec<E::Fr>,
+        user_key: &UserKey<E>,
+        rng: &mut R,
+    ) -> Self
+    where
+        R: Rng + CryptoRng,
+    {
+        let serial = E::Fr::rand(rng);
+        let ds_randomness = E::Fr::rand(rng);
+        let commitment = parameters.commit(&counters, serial, ds_randomness, user_key.secret_key);
+
+        PendingToken {
+            counters,
+            serial,
+            ds_randomness,
+            commitment,
+        }
+    }
+
+    /// Completes the token with the signature of the issuer, which is verified
+    pub fn finish(
+        self,
+        parameters: &IssuerParameters<E>,
+        signature: SpsEqSignature<E>,
+    ) -> Result<Token<E>, SpsEqSignatureError> {
+        let message = vec![self.commitment, E::G1Projective::prime_subgroup_generator()];
+        parameters.public_key.verify(&message, &signature)?;
+
+        Ok(Token {
+            counters: self.counters,
+            serial: self.serial,
+            ds_randomness: self.ds_randomness,
+            message,
+            signature,
+        })
+    }
+}
+
+impl<E: PairingEngine> Token<E> {
+    /// Counters of the token
+    pub fn counters(&self) -> &[E::Fr] {
+        &self.counters
+    }
+
+    /// Shows the token as a fresh representative, returning the showing and the witness
+    /// `(1/f, c_1, ..., c_n, u, x)` of the showing relation
+    fn show<R>(
+        &self,
+        user_key: &UserKey<E>,
+        challenge: E::Fr,
+        rng: &mut R,
+    ) -> (TokenShow<E>, Vec<E::Fr>)
+    where
+        R: Rng + CryptoRng,
+    {
+        let (signature, message, (f, _)) = self
+            .signature
+            .generate_new_repr_with_randomness(&self.message, rng);
+        let show = TokenShow {
+            message,
+            signature,
+            double_spend_tag: DoubleSpendTag {
+                serial: self.serial,
+                challenge,
+                tag: user_key.secret_key * challenge + self.ds_randomness,
+            },
+        };
+
+        let mut witness = vec![f.inverse().expect("It will never be zero")];
+        witness.extend_from_slice(&self.counters);
+        witness.push(self.ds_randomness);
+        witness.push(user_key.secret_key);
+        (show, witness)
+    }
+
+    /// Requests to add `increments` to the counters of the token, answering the challenge of
+    /// the issuer. The token must not be used again once the request is sent.
+    pub fn request_accumulate<R>(
+        &self,
+        parameters: &IssuerParameters<E>,
+        user_key: &UserKey<E>,
+        increments: &[E::Fr],
+        challenge: E::Fr,
+        rng: &mut R,
+    ) -> Result<(AccumulateRequest<E>, PendingToken<E>), SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if increments.len() != parameters.counters {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let counters = self
+            .counters
+            .iter()
+            .zip(increments)
+            .map(|(counter, increment)| *counter + increment)
+            .collect();
+        let pending = PendingToken::new(parameters, counters, user_key, rng);
+        let (show, mut witness) = self.show(user_key, challenge, rng);
+        witness.push(pending.serial);
+        witness.push(pending.ds_randomness);
+
+        let mut relation = parameters.show_relation(&show, 2)?;
+        parameters.add_update_equation(&mut relation, pending.commitment, increments)?;
+        let proof = relation.prove(&mut Transcript::new(b"bba-accumulate"), &witness, rng)?;
+
+        let request = AccumulateRequest {
+            show,
+            commitment: pending.commitment,
+            proof,
+        };
+        Ok((request, pending))
+    }
+
+    /// Requests to redeem the token under `policy`, answering the challenge of the issuer. The
+    /// reward is the inner product of the counters with the policy.
+    pub fn request_redeem<R>(
+        &self,
+        parameters: &IssuerParameters<E>,
+        user_key: &UserKey<E>,
+        policy: &[E::Fr],
+        challenge: E::Fr,
+        rng: &mut R,
+    ) -> Result<RedeemRequest<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if policy.len() != parameters.counters {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let (show, witness) = self.show(user_key, challenge, rng);
+        let reward = policy
+            .iter()
+            .zip(&self.counters)
+            .map(|(weight, counter)| *weight * counter)
+            .sum();
+
+        let proof = parameters.redeem_relation(&show, policy, reward)?.prove(
+            &mut Transcript::new(b"bba-redeem"),
+            &witness,
+            rng,
+        )?;
+
+        Ok(RedeemRequest {
+            show,
+            reward,
+            proof,
+        })
+    }
+}
+
+impl<E: PairingEngine> SpentTokens<E> {
+    /// Creates an empty set of spent tokens
+    pub fn new() -> Self {
+        SpentTokens {
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// Records the tag of a shown token, failing if a token with the same serial number was
+    /// shown before. Use `identify` with the tag returned by `get` to find the double-spender.
+    pub fn insert(&mut self, tag: &DoubleSpendTag<E>) -> Result<(), SpsEqSignatureError> {
+        if self.tags.contains_key(&tag.serial) {
+            return Err(SpsEqSignatureError::DoubleSpending);
+        }
+        self.tags.insert(tag.serial, tag.clone());
+        Ok(())
+    }
+
+    /// Returns the tag recorded for a serial number
+    pub fn get(&self, serial: &E::Fr) -> Option<&DoubleSpendTag<E>> {
+        self.tags.get(serial)
+    }
+}
+
+impl<E: PairingEngine> Default for SpentTokens<E> {
+    fn default() -> Self {
+        SpentTokens::new()
+    }
+}
+
+/// Recovers the public key of a user from two double-spending tags of the same token with
+/// different challenges
+pub fn identify<E: PairingEngine>(
+    first: &DoubleSpendTag<E>,
+    second: &DoubleSpendTag<E>,
+) -> Option<E::G1Projective> {
+    if first.serial != second.serial {
+        return None;
+    }
+    let secret_key = (first.tag - second.tag) * (first.challenge - second.challenge).inverse()?;
+    Some(E::G1Projective::prime_subgroup_generator().mul(secret_key.into_repr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use rand::thread_rng;
+
+    fn issue_token(issuer: &Issuer<Bls12_381>, user_key: &UserKey<Bls12_381>) -> Token<Bls12_381> {
+        let rng = &mut thread_rng();
+        let (request, pending) = user_key.request_issuance(&issuer.parameters, rng).unwrap();
+        let signature = issuer.issue(&request, rng).unwrap();
+        pending.finish(&issuer.parameters, signature).unwrap()
+    }
+
+    #[test]
+    fn test_token_lifecycle() {
+        let rng = &mut thread_rng();
+        let issuer = Issuer::<Bls12_381>::new(3, rng);
+        let parameters = &issuer.parameters;
+        let user_key = UserKey::new(rng);
+        let mut spent_tokens = SpentTokens::new();
+
+        let token = issue_token(&issuer, &user_key);
+        assert_eq!(token.counters(), &[Fr::zero(); 3]);
+
+        let increments = [Fr::from(1u64), Fr::from(5u64), Fr::from(0u64)];
+        let challenge = Fr::rand(rng);
+        let (request, pending) = token
+            .request_accumulate(parameters, &user_key, &increments, challenge, rng)
+            .unwrap();
+        let signature = issuer
+            .accumulate(&request, &increments, challenge, &mut spent_tokens, rng)
+            .unwrap();
+        let token = pending.finish(parameters, signature).unwrap();
+
+        let (request, pending) = token
+            .request_accumulate(parameters, &user_key, &increments, challenge, rng)
+            .unwrap();
+        let signature = issuer
+            .accumulate(&request, &increments, challenge, &mut spent_tokens, rng)
+            .unwrap();
+        let token = pending.finish(parameters, signature).unwrap();
+        assert_eq!(
+            token.counters(),
+            &[Fr::from(2u64), Fr::from(10u64), Fr::from(0u64)]
+        );
+
+        let policy = [Fr::from(3u64), Fr::from(1u64), Fr::from(7u64)];
+        let challenge = Fr::rand(rng);
+        let request = token
+            .request_redeem(parameters, &user_key, &policy, challenge, rng)
+            .unwrap();
+        assert_eq!(request.reward, Fr::from(16u64));
+        assert!(issuer
+            .redeem(&request, &policy, challenge, &mut spent_tokens)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_requests_are_rejected() {
+        let rng = &mut thread_rng();
+        let issuer = Issuer::<Bls12_381>::new(2, rng);
+        let parameters = &issuer.parameters;
+        let user_key = UserKey::new(rng);
+        let mut spent_tokens = SpentTokens::new();
+        let token = issue_token(&issuer, &user_key);
+
+        // The user cannot claim another public key at issuance
+        let (mut request, _) = user_key.request_issuance(parameters, rng).unwrap();
+        request.user_public_key = UserKey::<Bls12_381>::new(rng).public_key;
+        assert_eq!(
+            issuer.issue(&request, rng),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        // Increments other than the ones requested by the issuer are rejected
+        let increments = [Fr::from(1u64), Fr::from(1u64)];
+        let challenge = Fr::rand(rng);
+        let (request, _) = token
+            .request_accumulate(parameters, &user_key, &increments, challenge, rng)
+            .unwrap();
+        assert_eq!(
+            issuer.accumulate(
+                &request,
+                &[Fr::from(1u64), Fr::from(2u64)],
+                challenge,
+                &mut spent_tokens,
+                rng
+            ),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+        assert_eq!(
+            issuer.accumulate(&request, &increments, Fr::rand(rng), &mut spent_tokens, rng),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        // The reward must match the counters
+        let policy = [Fr::from(1u64), Fr::from(1u64)];
+        let mut request = token
+            .request_redeem(parameters, &user_key, &policy, challenge, rng)
+            .unwrap();
+        request.reward += Fr::from(1u64);
+        assert_eq!(
+            issuer.redeem(&request, &policy, challenge, &mut spent_tokens),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_double_spending_identifies_the_user() {
+        let rng = &mut thread_rng();
+        let issuer = Issuer::<Bls12_381>::new(2, rng);
+        let parameters = &issuer.parameters;
+        let user_key = UserKey::new(rng);
+        let mut spent_tokens = SpentTokens::new();
+        let token = issue_token(&issuer, &user_key);
+
+        let increments = [Fr::from(1u64), Fr::from(1u64)];
+        let first_challenge = Fr::rand(rng);
+        let (request, _) = token
+            .request_accumulate(parameters, &user_key, &increments, first_challenge, rng)
+            .unwrap();
+        assert!(issuer
+            .accumulate(
+                &request,
+                &increments,
+                first_challenge,
+                &mut spent_tokens,
+                rng
+            )
+            .is_ok());
+
+        let second_challenge = Fr::rand(rng);
+        let (request, _) = token
+            .request_accumulate(parameters, &user_key, &increments, second_challenge, rng)
+            .unwrap();
+        assert_eq!(
+            issuer.accumulate(
+                &request,
+                &increments,
+                second_challenge,
+                &mut spent_tokens,
+                rng
+            ),
+            Err(SpsEqSignatureError::DoubleSpending)
+        );
+
+        let second_tag = &request.show.double_spend_tag;
+        let first_tag = spent_tokens.get(&second_tag.serial).unwrap();
+        assert_eq!(identify(first_tag, second_tag), Some(user_key.public_key));
+    }
+}
@@ -0,0 +1,329 @@
+//! Module describing the blind issuance of SPS-EQ signatures over commitments of users.
+//!
+//! The user commits to its attributes $a_i$ as $C = \sum_{i\in\left[n\right]} a_i B_i + r B_0$
+//! under the bases of the issuer, for a fresh $r$, and the signed message is the class of
+//! $(C, g)$. The issuance takes two rounds:
+//!
+//! * The user picks $f\in_R\mathbb Z_p^*$ and sends the representative $f(C, g)$ with a proof
+//!   of knowledge of its opening, see [`IssuanceRequest`].
+//! * The issuer verifies the proof and signs the representative, see [`IssuanceResponse`].
+//!
+//! The user verifies the signature, and unblinds it by changing the representation with
+//! $1/f$, which yields a signature over $(C, g)$. As the issuer only sees the uniformly
+//! distributed representative $f(C, g)$, it learns neither the attributes nor the
+//! representatives the user later shows.
+
+use alloc::vec::Vec;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use merlin::Transcript;
+use rand::{CryptoRng, Rng};
+
+use crate::errors::*;
+use crate::placement::*;
+use crate::proofs::OpeningProof;
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
+
+/// Public parameters of an issuer of blind signatures
+#[derive(Debug)]
+pub struct IssuerParameters<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Bases of the commitments: the base of the blinding randomness, then one per attribute
+    bases: Vec<P::Message>,
+    /// Key verifying the signatures over commitments
+    pub public_key: PublicKey<E, P>,
+}
+
+/// Issuer of blind signatures, holding the signing key
+#[derive(Debug)]
+pub struct Issuer<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    signing_key: SigningKey<E, P>,
+    /// Public parameters of the issuer
+    pub parameters: IssuerParameters<E, P>,
+}
+
+/// First round: blinded representative of the commitment of the user, with a proof of
+/// knowledge of its opening
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuanceRequest<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Representative `f (C, g)` of the commitment
+    pub message: Vec<P::Message>,
+    proof: OpeningProof<P::Message>,
+}
+
+/// Second round: signature of the issuer over the blinded representative
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuanceResponse<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Signature over the representative of the request
+    pub signature: SpsEqSignature<E, P>,
+}
+
+/// State of the user between the request and the response
+#[derive(Clone, Debug)]
+pub struct PendingIssuance<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    opening: Vec<E::Fr>,
+    blinded_message: Vec<P::Message>,
+    f: E::Fr,
+}
+
+/// Issuance request to send to the issuer, with the state the user keeps to unblind the response
+pub type PendingRequest<E, P = MessagesInG1> = (IssuanceRequest<E, P>, PendingIssuance<E, P>);
+
+/// Signature over the commitment `(C, g)` to the attributes of the user, with its opening
+#[derive(Clone, Debug)]
+pub struct SignedCommitment<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// The message `(C, g)`
+    pub message: Vec<P::Message>,
+    /// Signature over the message
+    pub signature: SpsEqSignature<E, P>,
+    opening: Vec<E::Fr>,
+}
+
+impl<E: PairingEngine, P: Placement<E>> IssuerParameters<E, P> {
+    /// Returns the bases of the commitments, starting with the base of the blinding randomness
+    pub fn bases(&self) -> &[P::Message] {
+        &self.bases
+    }
+
+    /// Number of attributes of a commitment
+    pub fn attributes(&self) -> usize {
+        self.bases.len() - 1
+    }
+
+    /// Creates an issuance request for `attributes`, returning the request to send to the
+    /// issuer and the state needed to unblind the response
+    pub fn request<R>(
+        &self,
+        attributes: &[E::Fr],
+        rng: &mut R,
+    ) -> Result<PendingRequest<E, P>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if attributes.len() != self.attributes() {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let mut opening = vec![E::Fr::rand(rng)];
+        opening.extend_from_slice(attributes);
+        let commitment: P::Message = opening
+            .iter()
+            .zip(self.bases.iter())
+            .map(|(value, base)| base.mul(value.into_repr()))
+            .sum();
+
+        let f: E::Fr = random_nonzero_scalar(rng);
+        let blinded_message = vec![
+            commitment.mul(f.into_repr()),
+            P::Message::prime_subgroup_generator().mul(f.into_repr()),
+        ];
+        let proof = OpeningProof::prove(
+            &mut Transcript::new(b"blind-issuance"),
+            &self.bases,
+            &blinded_message,
+            &opening,
+            f,
+            rng,
+        )?;
+
+        let request = IssuanceRequest {
+            message: blinded_message.clone(),
+            proof,
+        };
+        let pending = PendingIssuance {
+            opening,
+            blinded_message,
+            f,
+        };
+        Ok((request, pending))
+    }
+
+    /// Verifies the proof of well-formedness of an issuance request
+    pub fn verify_request(
+        &self,
+        request: &IssuanceRequest<E, P>,
+    ) -> Result<(), SpsEqSignatureError> {
+        if request.message.iter().any(|point| point.is_zero()) {
+            return Err(SpsEqSignatureError::IdentityMessageComponent);
+        }
+
+        request.proof.verify(
+            &mut Transcript::new(b"blind-issuance"),
+            &self.bases,
+            &request.message,
+        )
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> Issuer<E, P> {
+    /// Sets up an issuer of blind signatures over commitments to `attributes` attributes
+    pub fn new<R>(attributes: usize, rng: &mut R) -> Self
+    where
+        R: Rng + CryptoRng,
+    {
+        let signing_key = SigningKey::new(2, rng);
+        let bases = (0..attributes + 1).map(|_| P::Message::rand(rng)).collect();
+        let parameters = IssuerParameters {
+            bases,
+            public_key: PublicKey::from(&signing_key),
+        };
+
+        Issuer {
+            signing_key,
+            parameters,
+        }
+    }
+
+    /// Verifies an issuance request and signs its blinded representative
+    pub fn issue<R>(
+        &self,
+        request: &IssuanceRequest<E, P>,
+        rng: &mut R,
+    ) -> Result<IssuanceResponse<E, P>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        self.parameters.verify_request(request)?;
+        Ok(IssuanceResponse {
+            signature: self.signing_key.sign(&request.message, rng)?,
+        })
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> PendingIssuance<E, P> {
+    /// Verifies the response of the issuer, and unblinds the signature into a signature over
+    /// the commitment `(C, g)`
+    pub fn finish<R>(
+        self,
+        parameters: &IssuerParameters<E, P>,
+        response: IssuanceResponse<E, P>,
+        rng: &mut R,
+    ) -> Result<SignedCommitment<E, P>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        parameters
+            .public_key
+            .verify_strict(&self.blinded_message, &response.signature)?;
+
+        let f_inverse = self
+            .f
+            .inverse()
+            .ok_or(SpsEqSignatureError::InvalidRandomness)?;
+        let (signature, message) = response.signature.change_repr_with(
+            &self.blinded_message,
+            f_inverse,
+            random_nonzero_scalar(rng),
+        )?;
+
+        Ok(SignedCommitment {
+            message,
+            signature,
+            opening: self.opening,
+        })
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> SignedCommitment<E, P> {
+    /// Returns the attributes of the commitment
+    pub fn attributes(&self) -> &[E::Fr] {
+        &self.opening[1..]
+    }
+
+    /// Returns the opening of the commitment: the blinding randomness, then the attributes
+    pub fn opening(&self) -> &[E::Fr] {
+        &self.opening
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_blind_issuance() {
+        let rng = &mut thread_rng();
+        let issuer = Issuer::<Bls12_381>::new(3, rng);
+        let parameters = &issuer.parameters;
+
+        let attributes: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let (request, pending) = parameters.request(&attributes, rng).unwrap();
+        assert!(parameters.verify_request(&request).is_ok());
+
+        let response = issuer.issue(&request, rng).unwrap();
+        let signed = pending.finish(parameters, response, rng).unwrap();
+        assert_eq!(signed.attributes(), attributes.as_slice());
+        assert_eq!(signed.message[1], G1::prime_subgroup_generator());
+        assert_ne!(signed.message[0], request.message[0]);
+        assert!(parameters
+            .public_key
+            .verify_strict(&signed.message, &signed.signature)
+            .is_ok());
+
+        // The user shows a fresh representative and proves knowledge of its opening
+        let (signature, message, (f, _)) = signed
+            .signature
+            .generate_new_repr_with_randomness(&signed.message, rng);
+        let proof = OpeningProof::prove(
+            &mut Transcript::new(b"show"),
+            parameters.bases(),
+            &message,
+            signed.opening(),
+            f,
+            rng,
+        )
+        .unwrap();
+        assert!(parameters.public_key.verify(&message, &signature).is_ok());
+        assert!(proof
+            .verify(&mut Transcript::new(b"show"), parameters.bases(), &message)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_blind_issuance_rejects_invalid_rounds() {
+        let rng = &mut thread_rng();
+        let issuer = Issuer::<Bls12_381>::new(2, rng);
+        let parameters = &issuer.parameters;
+        let attributes: Vec<Fr> = (0..2).map(|_| Fr::rand(rng)).collect();
+
+        assert_eq!(
+            parameters.request(&attributes[..1], rng).unwrap_err(),
+            SpsEqSignatureError::UnmatchedCapacity
+        );
+
+        // A representative that does not match the proof is rejected
+        let (mut request, pending) = parameters.request(&attributes, rng).unwrap();
+        request.message[0] += G1::prime_subgroup_generator();
+        assert_eq!(
+            issuer.issue(&request, rng).unwrap_err(),
+            SpsEqSignatureError::InvalidProof
+        );
+
+        // A signature over another message is rejected by the user
+        let (other_request, _) = parameters.request(&attributes, rng).unwrap();
+        let response = issuer.issue(&other_request, rng).unwrap();
+        assert_eq!(
+            pending.finish(parameters, response, rng).unwrap_err(),
+            SpsEqSignatureError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn test_blind_issuance_in_g2() {
+        let rng = &mut thread_rng();
+        let issuer = Issuer::<Bls12_381, MessagesInG2>::new(2, rng);
+        let parameters = &issuer.parameters;
+        let attributes: Vec<Fr> = (0..2).map(|_| Fr::rand(rng)).collect();
+
+        let (request, pending) = parameters.request(&attributes, rng).unwrap();
+        let response = issuer.issue(&request, rng).unwrap();
+        let signed = pending.finish(parameters, response, rng).unwrap();
+        assert!(parameters
+            .public_key
+            .verify(&signed.message, &signed.signature)
+            .is_ok());
+    }
+}
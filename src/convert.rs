@@ -0,0 +1,139 @@
+//! Module describing the conversion of keys and signatures to another representative of the
+//! key class, as in mercurial signatures.
+//!
+//! Keys $sk = \lbrace x_i\rbrace_{i\in\left[l\right]}$ and $sk' = \lbrace \rho x_i\rbrace_{i\in\left[l\right]}$
+//! belong to the same class for any $\rho\in\mathbb Z_p^*$, and so do the corresponding public
+//! keys. A signature $\sigma = (Z, Y_1, Y_2)$ over $M$ under $pk$ is converted into a signature
+//! under $pk^\rho$ by picking $\psi\in_R\mathbb Z_p^*$ and computing
+//! $\sigma' = (\psi\rho Z, Y_1^{\frac{1}{\psi}}, Y_2^{\frac{1}{\psi}})$.
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, Zero};
+
+use crate::errors::*;
+use crate::placement::*;
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
+use rand::{CryptoRng, Rng};
+
+/// Converts a signing key to the representative of its class given by `rho`, which must be
+/// nonzero
+pub fn convert_sk<E: PairingEngine, P: Placement<E>>(
+    signing_key: &SigningKey<E, P>,
+    rho: E::Fr,
+) -> Result<SigningKey<E, P>, SpsEqSignatureError> {
+    if rho.is_zero() {
+        return Err(SpsEqSignatureError::InvalidRandomness);
+    }
+
+    SigningKey::from(signing_key.into_iter().map(|key| key * rho).collect())
+}
+
+/// Converts a public key to the representative of its class given by `rho`, which must be
+/// nonzero. The result is the public key of `convert_sk(sk, rho)`.
+pub fn convert_pk<E: PairingEngine, P: Placement<E>>(
+    public_key: &PublicKey<E, P>,
+    rho: E::Fr,
+) -> Result<PublicKey<E, P>, SpsEqSignatureError> {
+    if rho.is_zero() {
+        return Err(SpsEqSignatureError::InvalidRandomness);
+    }
+
+    let rho = rho.into_repr();
+    Ok(PublicKey::from_public_keys(
+        public_key.into_iter().map(|key| key.mul(rho)).collect(),
+    ))
+}
+
+/// Converts a signature over `message` under `public_key` into a signature over the same
+/// message under `convert_pk(public_key, rho)`. The signature is verified first, and fresh
+/// randomness makes the converted signature independent of the original one.
+pub fn convert_sig<E, P, R>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    signature: &SpsEqSignature<E, P>,
+    rho: E::Fr,
+    rng: &mut R,
+) -> Result<SpsEqSignature<E, P>, SpsEqSignatureError>
+where
+    E: PairingEngine,
+    P: Placement<E>,
+    R: Rng + CryptoRng,
+{
+    if rho.is_zero() {
+        return Err(SpsEqSignatureError::InvalidRandomness);
+    }
+    public_key.verify(message, signature)?;
+
+    // Changing the representation of the message with f = 1 only re-randomises the signature
+    let psi = random_nonzero_scalar(rng);
+    let (mut converted, _) = signature.change_repr_with(message, E::Fr::one(), psi)?;
+    converted.Z *= rho;
+
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_converted_signature_verifies_under_converted_key() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+
+        let rho: Fr = random_nonzero_scalar(rng);
+        let converted_sk = convert_sk(&sk, rho).unwrap();
+        let converted_pk = convert_pk(&pk, rho).unwrap();
+        assert_eq!(PublicKey::from(&converted_sk), converted_pk);
+
+        let converted_signature = convert_sig(&pk, &message, &signature, rho, rng).unwrap();
+        assert!(converted_pk.verify(&message, &converted_signature).is_ok());
+        assert!(pk.verify(&message, &converted_signature).is_err());
+        assert_ne!(converted_signature.Y, signature.Y);
+
+        // Signatures under the converted key still support a change of representation
+        let (new_signature, new_message) = converted_signature.generate_new_repr(&message, rng);
+        assert!(converted_pk.verify(&new_message, &new_signature).is_ok());
+
+        // Signatures of the converted signing key verify under the converted public key
+        let signature = converted_sk.sign(&message, rng).unwrap();
+        assert!(converted_pk.verify(&message, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_conversion_rejects_invalid_input() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(2, rng);
+        let pk = PublicKey::from(&sk);
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+
+        assert_eq!(
+            convert_sk(&sk, Fr::zero()),
+            Err(SpsEqSignatureError::InvalidRandomness)
+        );
+        assert_eq!(
+            convert_pk(&pk, Fr::zero()).unwrap_err(),
+            SpsEqSignatureError::InvalidRandomness
+        );
+        assert_eq!(
+            convert_sig(&pk, &message, &signature, Fr::zero(), rng),
+            Err(SpsEqSignatureError::InvalidRandomness)
+        );
+
+        let different_message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        assert_eq!(
+            convert_sig(&pk, &different_message, &signature, Fr::rand(rng), rng),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+    }
+}
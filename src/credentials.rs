@@ -0,0 +1,544 @@
+//! Module describing attribute-based credentials built on SPS-EQ signatures and set
+//! commitments, following the constant-size credentials of the [paper][sps-eq] of Fuchsbauer,
+//! Hanser and Slamanig.
+//!
+//! The attributes of a credential are committed as a set with the scheme of the
+//! [`set_commitment`](crate::set_commitment) module, as $C = \rho f_S(a) g_1$ with the opening
+//! $\rho$, and subsets of them are opened with a witness.
+//!
+//! A credential is a signature over the class of $(C, rC, g_1)$, for a secret $r$ of the user:
+//!
+//! * Issuance: the user sends its attributes and the message, and proves knowledge of $\rho$
+//!   and $r$ with $C = \rho Q$, where the issuer computes $Q = f_S(a) g_1$ from the attributes
+//!   and the public powers. The opening $\rho$ stays with the user, so the issuer cannot
+//!   recognise the commitment in later showings. The issuer checks the proof and signs the
+//!   message.
+//! * Showing: the user moves the credential to the representative $\mu(C, rC, g_1)$, opens the
+//!   disclosed subset of the attributes with the opening $\mu\rho$, and proves knowledge of
+//!   $\mu$. The proof is bound to the representative, the signature, the witness and the
+//!   disclosed attributes. The showing consists of three group elements, a signature, a witness
+//!   and a proof, whatever the number of attributes.
+//!
+//! Unlike the signatures, credentials are not generic over the [`Placement`] of the messages,
+//! and always use [`MessagesInG1`]. The set commitments are elements of $\mathbb{G}_1$,
+//! verified against the powers of the trapdoor in $\mathbb{G}_2$, and the signed message
+//! contains the commitment itself, so it must lie in the message group. Moving the messages to
+//! $\mathbb{G}_2$ would require committing in $\mathbb{G}_2$, and make the showings larger.
+//!
+//! [`Placement`]: crate::placement::Placement
+//! [`MessagesInG1`]: crate::placement::MessagesInG1
+//! [sps-eq]: https://eprint.iacr.org/2014/944.pdf
+
+use alloc::vec::Vec;
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField};
+use merlin::Transcript;
+use rand::{CryptoRng, Rng};
+
+use crate::errors::*;
+use crate::proofs::{LinearProof, LinearRelation};
+use crate::serialization::*;
+use crate::set_commitment::TrustedSetup;
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
+
+/// Credential issuer, holding the signing key
+#[derive(Debug)]
+pub struct Issuer<E: PairingEngine> {
+    signing_key: SigningKey<E>,
+    /// Key verifying the credentials of the issuer
+    pub public_key: PublicKey<E>,
+}
+
+/// Request for a credential over a set of attributes
+#[derive(Clone, Debug)]
+pub struct CredentialRequest<E: PairingEngine> {
+    /// Attributes of the credential
+    pub attributes: Vec<E::Fr>,
+    /// Message `(C, rC, g1)` to sign
+    pub message: Vec<E::G1Projective>,
+    proof: LinearProof<E::G1Projective>,
+}
+
+/// Credential requested by a user and not yet signed by the issuer
+#[derive(Clone, Debug)]
+pub struct PendingCredential<E: PairingEngine> {
+    attributes: Vec<E::Fr>,
+    opening: E::Fr,
+    message: Vec<E::G1Projective>,
+}
+
+/// Credential held by a user
+#[derive(Clone, Debug)]
+pub struct Credential<E: PairingEngine> {
+    attributes: Vec<E::Fr>,
+    opening: E::Fr,
+    message: Vec<E::G1Projective>,
+    signature: SpsEqSignature<E>,
+}
+
+/// Showing of a credential, disclosing a subset of its attributes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CredentialShow<E: PairingEngine> {
+    /// Representative of the credential
+    pub message: Vec<E::G1Projective>,
+    /// Signature over the representative
+    pub signature: SpsEqSignature<E>,
+    /// Witness of the disclosed attributes
+    pub witness: E::G1Projective,
+    proof: LinearProof<E::G1Projective>,
+}
+
+impl<E: PairingEngine> Issuer<E> {
+    /// Generates the key pair of a credential issuer
+    pub fn new<R>(rng: &mut R) -> Self
+    where
+        R: Rng + CryptoRng,
+    {
+        let signing_key = SigningKey::new(3, rng);
+        let public_key = PublicKey::from(&signing_key);
+        Issuer {
+            signing_key,
+            public_key,
+        }
+    }
+
+    /// Checks that the request commits to its attributes, without learning the opening of the
+    /// commitment, and signs the credential
+    pub fn issue<R>(
+        &self,
+        parameters: &TrustedSetup<E>,
+        request: &CredentialRequest<E>,
+        rng: &mut R,
+    ) -> Result<SpsEqSignature<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if request.message.len() != 3 {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+        if request.message[2] != E::G1Projective::prime_subgroup_generator() {
+            return Err(SpsEqSignatureError::InvalidProof);
+        }
+
+        // Q = f_S(a) g1, the commitment to the attributes with the opening 1
+        let set_point = parameters.commit_with(&request.attributes, E::Fr::one())?;
+        issuance_relation(&set_point, &request.message)?
+            .verify(&mut Transcript::new(b"credential-issuance"), &request.proof)?;
+        self.signing_key.sign(&request.message, rng)
+    }
+}
+
+impl<E: PairingEngine> CredentialRequest<E> {
+    /// Requests a credential over `attributes`, returning the request to send to the issuer
+    /// and the pending credential
+    pub fn new<R>(
+        parameters: &TrustedSetup<E>,
+        attributes: &[E::Fr],
+        rng: &mut R,
+    ) -> Result<(Self, PendingCredential<E>), SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        let (commitment, opening) = parameters.commit(attributes, rng)?;
+        let secret: E::Fr = random_nonzero_scalar(rng);
+        let message = vec![
+            commitment,
+            commitment.mul(secret.into_repr()),
+            E::G1Projective::prime_subgroup_generator(),
+        ];
+        let set_point = parameters.commit_with(attributes, E::Fr::one())?;
+        let proof = issuance_relation(&set_point, &message)?.prove(
+            &mut Transcript::new(b"credential-issuance"),
+            &[opening, secret],
+            rng,
+        )?;
+
+        let request = CredentialRequest {
+            attributes: attributes.to_vec(),
+            message: message.clone(),
+            proof,
+        };
+        let pending = PendingCredential {
+            attributes: attributes.to_vec(),
+            opening,
+            message,
+        };
+        Ok((request, pending))
+    }
+}
+
+impl<E: PairingEngine> PendingCredential<E> {
+    /// Verifies the signature of the issuer and returns the credential
+    pub fn finish(
+        self,
+        public_key: &PublicKey<E>,
+        signature: SpsEqSignature<E>,
+    ) -> Result<Credential<E>, SpsEqSignatureError> {
+        public_key.verify_strict(&self.message, &signature)?;
+        Ok(Credential {
+            attributes: self.attributes,
+            opening: self.opening,
+            message: self.message,
+            signature,
+        })
+    }
+}
+
+impl<E: PairingEngine> Credential<E> {
+    /// Returns the attributes of the credential
+    pub fn attributes(&self) -> &[E::Fr] {
+        &self.attributes
+    }
+
+    /// Shows the credential, disclosing the subset `disclosed` of its attributes. The caller may
+    /// append a nonce of the verifier to the transcript beforehand, to prevent replays. The
+    /// verifier must pass the disclosed attributes in the same order.
+    pub fn show<R>(
+        &self,
+        parameters: &TrustedSetup<E>,
+        disclosed: &[E::Fr],
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Result<CredentialShow<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        let mu = random_nonzero_scalar(rng);
+        let (signature, message) =
+            self.signature
+                .change_repr_with(&self.message, mu, random_nonzero_scalar(rng))?;
+        let witness = parameters.open_subset(&self.attributes, mu * self.opening, disclosed)?;
+        append_show(transcript, &message, &signature, &witness, disclosed)?;
+        let proof = randomness_relation(&message)?.prove(transcript, &[mu], rng)?;
+
+        Ok(CredentialShow {
+            message,
+            signature,
+            witness,
+            proof,
+        })
+    }
+}
+
+impl<E: PairingEngine> CredentialShow<E> {
+    /// Verifies the showing of a credential of the issuer with key `public_key`, disclosing the
+    /// attributes `disclosed`
+    pub fn verify(
+        &self,
+        parameters: &TrustedSetup<E>,
+        public_key: &PublicKey<E>,
+        disclosed: &[E::Fr],
+        transcript: &mut Transcript,
+    ) -> Result<(), SpsEqSignatureError> {
+        public_key.verify_strict(&self.message, &self.signature)?;
+        parameters.verify_subset(&self.message[0], disclosed, &self.witness)?;
+        append_show(
+            transcript,
+            &self.message,
+            &self.signature,
+            &self.witness,
+            disclosed,
+        )?;
+        randomness_relation(&self.message)?.verify(transcript, &self.proof)
+    }
+
+    /// Convert a `CredentialShow` to an array of bytes, using compressed points
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut writer = Vec::new();
+        write_version(&mut writer);
+        write_point_encoding(&mut writer, true);
+        write_length(&mut writer, self.message.len())?;
+        let mut points = self.message.clone();
+        points.push(self.witness);
+        for point in E::G1Projective::batch_normalization_into_affine(&points) {
+            write_point(&mut writer, &point, true)?;
+        }
+        self.signature.write_fields(&mut writer, true)?;
+        self.proof.write_fields(&mut writer)?;
+        Ok(writer)
+    }
+
+    /// Create a `CredentialShow` from an array of bytes produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
+        let mut reader = ByteReader::new(bytes);
+        reader.read_version()?;
+        let compressed = reader.read_point_encoding()?;
+        let length = reader.read_length(point_size::<E::G1Affine>(compressed))?;
+
+        // The length prefix comes from the input, so we do not preallocate from it
+        let mut message = Vec::new();
+        for _ in 0..length {
+            let point: E::G1Affine = reader.read_point(compressed)?;
+            message.push(point.into_projective());
+        }
+        let witness: E::G1Affine = reader.read_point(compressed)?;
+        let signature = SpsEqSignature::read_fields(&mut reader, compressed)?;
+        let proof = LinearProof::read_fields(&mut reader)?;
+        reader.finish()?;
+
+        Ok(CredentialShow {
+            message,
+            signature,
+            witness: witness.into_projective(),
+            proof,
+        })
+    }
+}
+
+/// Relation stating knowledge of `rho` and `r` with `rho Q = C` and `r C = rC`, for the
+/// message `(C, rC, g1)` and the point `Q` of the attributes
+fn issuance_relation<G: ProjectiveCurve>(
+    set_point: &G,
+    message: &[G],
+) -> Result<LinearRelation<G>, SpsEqSignatureError> {
+    if message.len() != 3 {
+        return Err(SpsEqSignatureError::UnmatchedCapacity);
+    }
+
+    let mut relation = LinearRelation::new(2);
+    relation.add_equation(vec![(0, *set_point)], message[0])?;
+    relation.add_equation(vec![(1, message[0])], message[1])?;
+    Ok(relation)
+}
+
+/// Relation stating knowledge of `mu` with `mu g1 = P`, for the representative `(C, rC, P)`
+fn randomness_relation<G: ProjectiveCurve>(
+    message: &[G],
+) -> Result<LinearRelation<G>, SpsEqSignatureError> {
+    if message.len() != 3 {
+        return Err(SpsEqSignatureError::UnmatchedCapacity);
+    }
+
+    let mut relation = LinearRelation::new(1);
+    relation.add_equation(vec![(0, G::prime_subgroup_generator())], message[2])?;
+    Ok(relation)
+}
+
+/// Appends the representative, the signature, the witness and the disclosed attributes of a
+/// showing to the transcript, so that its proof cannot be moved to another showing
+fn append_show<E: PairingEngine>(
+    transcript: &mut Transcript,
+    message: &[E::G1Projective],
+    signature: &SpsEqSignature<E>,
+    witness: &E::G1Projective,
+    disclosed: &[E::Fr],
+) -> Result<(), SpsEqSignatureError> {
+    let mut bytes = Vec::new();
+    write_length(&mut bytes, message.len())?;
+    let mut points = message.to_vec();
+    points.push(*witness);
+    for point in E::G1Projective::batch_normalization_into_affine(&points) {
+        write_point(&mut bytes, &point, true)?;
+    }
+    signature.write_fields(&mut bytes, true)?;
+    write_length(&mut bytes, disclosed.len())?;
+    for attribute in disclosed {
+        write_scalar(&mut bytes, attribute)?;
+    }
+
+    transcript.append_message(b"credential-show", &bytes);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    fn issue_credential(
+        parameters: &TrustedSetup<Bls12_381>,
+        issuer: &Issuer<Bls12_381>,
+        attributes: &[Fr],
+    ) -> Credential<Bls12_381> {
+        let rng = &mut thread_rng();
+        let (request, pending) = CredentialRequest::new(parameters, attributes, rng).unwrap();
+        let signature = issuer.issue(parameters, &request, rng).unwrap();
+        pending.finish(&issuer.public_key, signature).unwrap()
+    }
+
+    #[test]
+    fn test_show_credential() {
+        let rng = &mut thread_rng();
+        let parameters = TrustedSetup::<Bls12_381>::new(5, rng);
+        let issuer = Issuer::new(rng);
+        let attributes: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        let credential = issue_credential(&parameters, &issuer, &attributes);
+
+        for disclosed in [&attributes[..0], &attributes[1..3], &attributes[..]].iter() {
+            let show = credential
+                .show(&parameters, disclosed, &mut Transcript::new(b"test"), rng)
+                .unwrap();
+            assert!(show
+                .verify(
+                    &parameters,
+                    &issuer.public_key,
+                    disclosed,
+                    &mut Transcript::new(b"test")
+                )
+                .is_ok());
+        }
+
+        // Two showings of the same credential are different representatives
+        let first_show = credential
+            .show(
+                &parameters,
+                &attributes[..1],
+                &mut Transcript::new(b"test"),
+                rng,
+            )
+            .unwrap();
+        let second_show = credential
+            .show(
+                &parameters,
+                &attributes[..1],
+                &mut Transcript::new(b"test"),
+                rng,
+            )
+            .unwrap();
+        assert_ne!(first_show.message, second_show.message);
+    }
+
+    #[test]
+    fn test_invalid_show_is_rejected() {
+        let rng = &mut thread_rng();
+        let parameters = TrustedSetup::<Bls12_381>::new(3, rng);
+        let issuer = Issuer::new(rng);
+        let attributes: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let credential = issue_credential(&parameters, &issuer, &attributes);
+
+        let other_attribute = Fr::rand(rng);
+        assert_eq!(
+            credential
+                .show(
+                    &parameters,
+                    &[other_attribute],
+                    &mut Transcript::new(b"test"),
+                    rng
+                )
+                .unwrap_err(),
+            SpsEqSignatureError::AttributeNotInSet
+        );
+
+        let show = credential
+            .show(
+                &parameters,
+                &attributes[..1],
+                &mut Transcript::new(b"test"),
+                rng,
+            )
+            .unwrap();
+        assert_eq!(
+            show.verify(
+                &parameters,
+                &issuer.public_key,
+                &[other_attribute],
+                &mut Transcript::new(b"test")
+            ),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+        assert_eq!(
+            show.verify(
+                &parameters,
+                &issuer.public_key,
+                &attributes[..1],
+                &mut Transcript::new(b"other nonce")
+            ),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        let other_issuer = Issuer::<Bls12_381>::new(rng);
+        assert_eq!(
+            show.verify(
+                &parameters,
+                &other_issuer.public_key,
+                &attributes[..1],
+                &mut Transcript::new(b"test")
+            ),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_invalid_request_is_rejected() {
+        let rng = &mut thread_rng();
+        let parameters = TrustedSetup::<Bls12_381>::new(3, rng);
+        let issuer = Issuer::new(rng);
+        let attributes: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+
+        let too_many_attributes: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        assert_eq!(
+            CredentialRequest::new(&parameters, &too_many_attributes, rng).unwrap_err(),
+            SpsEqSignatureError::UnmatchedCapacity
+        );
+
+        // The issuer only signs commitments to the attributes of the request
+        let (mut request, _) = CredentialRequest::new(&parameters, &attributes, rng).unwrap();
+        request.attributes[0] = Fr::rand(rng);
+        assert_eq!(
+            issuer.issue(&parameters, &request, rng),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        // A commitment with a known opening but to other attributes is rejected as well
+        let (mut request, _) = CredentialRequest::new(&parameters, &attributes, rng).unwrap();
+        let (other_request, _) =
+            CredentialRequest::new(&parameters, &attributes[..2], rng).unwrap();
+        request.attributes = other_request.attributes;
+        assert_eq!(
+            issuer.issue(&parameters, &request, rng),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_show_proof_is_bound_to_the_showing() {
+        let rng = &mut thread_rng();
+        let parameters = TrustedSetup::<Bls12_381>::new(3, rng);
+        let issuer = Issuer::new(rng);
+        let attributes: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let credential = issue_credential(&parameters, &issuer, &attributes);
+
+        let show = credential
+            .show(
+                &parameters,
+                &attributes[..2],
+                &mut Transcript::new(b"test"),
+                rng,
+            )
+            .unwrap();
+        let verify = |show: &CredentialShow<Bls12_381>, disclosed: &[Fr]| {
+            show.verify(
+                &parameters,
+                &issuer.public_key,
+                disclosed,
+                &mut Transcript::new(b"test"),
+            )
+        };
+        assert!(verify(&show, &attributes[..2]).is_ok());
+
+        // A re-randomised signature over the same representative still verifies, but the proof
+        // no longer does
+        let mut other_signature = show.clone();
+        other_signature.signature = show
+            .signature
+            .change_repr_with(&show.message, Fr::one(), Fr::rand(rng))
+            .unwrap()
+            .0;
+        assert_eq!(
+            verify(&other_signature, &attributes[..2]),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        // The same witness opens the disclosed attributes in any order, but the proof commits
+        // to the order of the showing
+        let reordered = [attributes[1], attributes[0]];
+        assert_eq!(
+            verify(&show, &reordered),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+    }
+}
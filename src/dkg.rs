@@ -0,0 +1,1295 @@
+//! Module describing the distributed generation of SPS-EQ issuer keys, with the protocol of
+//! Gennaro, Jarecki, Krawczyk and Rabin.
+//!
+//! Each of the $n$ parties deals every secret key $x_i$ of the key vector: party $k$ picks
+//! polynomials $a_{k, i}$ and $b_{k, i}$ of degree $t - 1$, and privately sends the shares
+//! $(a_{k, i}(j), b_{k, i}(j))$ to every party $j$. The key vector is
+//! $x_i = \sum_{k\in Q} a_{k, i}(0)$ over the set $Q$ of qualified dealers, so that nobody knows
+//! it unless $t$ parties collude.
+//!
+//! With the joint Feldman protocol of Pedersen, dealers publish the commitments
+//! $a_{k, i, m} g_2$ right away. A rushing dealer then sees the contributions of the others
+//! before its own complaints and answers decide whether it is qualified, and can bias the joint
+//! key. Here, dealers first publish Pedersen commitments $C_{k, i, m} = a_{k, i, m} g_2 +
+//! b_{k, i, m} h_2$, which hide the constant terms, where $h_2$ is a generator whose discrete
+//! logarithm is unknown. $Q$ is fixed before the Feldman commitments
+//! $A_{k, i, m} = a_{k, i, m} g_2$ are revealed, and the polynomials of a qualified dealer that
+//! does not reveal consistent commitments are reconstructed from the shares of the other
+//! parties. The protocol takes six rounds:
+//!
+//! * Deal: every party broadcasts its Pedersen commitments and sends its shares.
+//! * Complain: every party checks its shares against the commitments with
+//!   $a_{k, i}(j) g_2 + b_{k, i}(j) h_2 = \sum_m j^m C_{k, i, m}$, and broadcasts a complaint
+//!   against every dealer whose shares are missing or invalid.
+//! * Respond: every dealer answers the complaints against it by broadcasting the disputed
+//!   shares.
+//! * Extract: a dealer is qualified if it broadcast well-formed commitments and answered every
+//!   complaint with valid shares. Every qualified dealer broadcasts its Feldman commitments.
+//! * Accuse: every party checks its shares with $a_{k, i}(j) g_2 = \sum_m j^m A_{k, i, m}$, and
+//!   broadcasts the shares of every qualified dealer that fail the check while matching the
+//!   Pedersen commitments. Such a dealer, or one whose Feldman commitments are missing or
+//!   malformed, is disqualified from extraction.
+//! * Reveal: every party broadcasts its shares of the dealers disqualified from extraction.
+//!
+//! Finally, every party interpolates the polynomials $a_{k, i}$ of the disqualified dealers
+//! from $t$ revealed shares that match the Pedersen commitments, and recomputes their Feldman
+//! commitments. As the decisions only depend on broadcast messages, all honest parties agree on
+//! $Q$ and on the joint public key $X_i = \sum_{k\in Q} A_{k, i, 0}$, and party $j$ holds the
+//! share $\sum_{k\in Q} a_{k, i}(j)$ of every $x_i$. Parties that drop out before dealing are
+//! left out of $Q$.
+//!
+//! The summed commitments also give the verification keys $\sum_{k\in Q} a_{k, i}(j) g_2$ of
+//! every party $j$, which make up a [`ThresholdKey`] together with the joint public key. The
+//! parties can then issue signatures together with the [`threshold`](crate::threshold) module,
+//! whose presignatures are generated with the same rounds.
+//!
+//! The messages of a round are exchanged with a [`Transport`], which must authenticate their
+//! senders and provide a reliable broadcast. With the `std` feature, the [`InProcessNetwork`]
+//! runs the parties in threads of the same process.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use core::fmt;
+use core::marker::PhantomData;
+use merlin::Transcript;
+use rand::{CryptoRng, Rng};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Condvar, Mutex};
+use zeroize::Zeroize;
+
+use crate::errors::*;
+use crate::placement::*;
+use crate::threshold::ThresholdKey;
+use crate::utils::{evaluate_commitments, evaluate_polynomial, interpolate_polynomial};
+
+/// Message of the key generation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DkgMessage<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Pedersen commitments of a dealer to the coefficients of its polynomials, one vector per
+    /// key
+    Commitments(Vec<Vec<P::Key>>),
+    /// Shares of a dealer for the recipient and shares of the blinding polynomials, one per key
+    Shares(Vec<E::Fr>, Vec<E::Fr>),
+    /// Complaint against a dealer
+    Complaint(usize),
+    /// Shares of a dealer for an accuser and shares of the blinding polynomials, revealed in
+    /// answer to its complaint
+    Response(usize, Vec<E::Fr>, Vec<E::Fr>),
+    /// Feldman commitments of a qualified dealer to the coefficients of its polynomials
+    FeldmanCommitments(Vec<Vec<P::Key>>),
+    /// Shares of a dealer and shares of the blinding polynomials, which match the Pedersen
+    /// commitments of the dealer but not its Feldman commitments
+    Accusation(usize, Vec<E::Fr>, Vec<E::Fr>),
+    /// Shares of the sender from a dealer disqualified from extraction, and shares of the
+    /// blinding polynomials
+    Reveal(usize, Vec<E::Fr>, Vec<E::Fr>),
+}
+
+/// Message of the key generation with its sender and recipient
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Index of the sending party
+    pub sender: usize,
+    /// Index of the receiving party, or `None` for a broadcast
+    pub recipient: Option<usize>,
+    /// The message
+    pub message: DkgMessage<E, P>,
+}
+
+/// Transport of the messages of the key generation between the parties
+pub trait Transport<E: PairingEngine, P: Placement<E>> {
+    /// Sends the messages of the party for the current round, and returns the messages of the
+    /// round that were broadcast or addressed to the party
+    fn exchange(
+        &mut self,
+        outgoing: Vec<Envelope<E, P>>,
+    ) -> Result<Vec<Envelope<E, P>>, SpsEqSignatureError>;
+}
+
+/// Party of the key generation, or of the generation of a presignature. It holds the secret
+/// polynomials of the party and the shares it received, so it cannot be cloned, its `Debug`
+/// output leaves them out, and they are erased when it is dropped.
+pub struct Participant<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    index: usize,
+    threshold: usize,
+    parties: usize,
+    sharings: Vec<Sharing>,
+    blinding_generator: P::Key,
+    polynomials: Vec<Vec<E::Fr>>,
+    blinding_polynomials: Vec<Vec<E::Fr>>,
+    commitments: BTreeMap<usize, Vec<Vec<P::Key>>>,
+    shares: BTreeMap<usize, Vec<E::Fr>>,
+    blinding_shares: BTreeMap<usize, Vec<E::Fr>>,
+    complaints: BTreeSet<(usize, usize)>,
+    answered: BTreeSet<(usize, usize)>,
+    qualified: Vec<usize>,
+    feldman_commitments: BTreeMap<usize, Vec<Vec<P::Key>>>,
+    disqualified: BTreeSet<usize>,
+    revealed: BTreeMap<usize, BTreeMap<usize, Vec<E::Fr>>>,
+}
+
+/// Shape of a secret dealt by every party: the number of coefficients of the polynomials, and
+/// whether their constant terms are zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Sharing {
+    pub(crate) coefficients: usize,
+    pub(crate) zero_constant: bool,
+}
+
+/// Shares of a party and summed commitments over the qualified dealers, one per secret
+pub(crate) struct JointSharing<E: PairingEngine, P: Placement<E>> {
+    pub(crate) index: usize,
+    pub(crate) shares: Vec<E::Fr>,
+    pub(crate) commitments: Vec<Vec<P::Key>>,
+    pub(crate) qualified: Vec<usize>,
+}
+
+/// Shares held by a party of every secret key of the key vector. As for [`Participant`], it
+/// cannot be cloned, its `Debug` output leaves the shares out, and they are erased when it is
+/// dropped.
+#[derive(PartialEq, Eq)]
+pub struct KeyShare<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Index of the party, starting at 1
+    pub index: usize,
+    shares: Vec<E::Fr>,
+    placement: PhantomData<P>,
+}
+
+/// Result of the key generation for a party
+#[derive(Debug)]
+pub struct DkgOutput<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Shares of the party
+    pub key_share: KeyShare<E, P>,
+    /// Joint public key, with the verification keys of the parties
+    pub threshold_key: ThresholdKey<E, P>,
+    /// Indices of the qualified dealers
+    pub qualified: Vec<usize>,
+}
+
+impl<E: PairingEngine, P: Placement<E>> Participant<E, P> {
+    /// Creates the party with index `index`, starting at 1, among `parties` parties generating
+    /// a key of capacity `capacity` shared with threshold `threshold`
+    pub fn new<R>(
+        index: usize,
+        threshold: usize,
+        parties: usize,
+        capacity: usize,
+        rng: &mut R,
+    ) -> Result<Self, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        let sharing = Sharing {
+            coefficients: threshold,
+            zero_constant: false,
+        };
+        Self::with_sharings(index, threshold, parties, vec![sharing; capacity], rng)
+    }
+
+    /// Creates a party dealing secrets of the given shapes, and requiring `threshold` qualified
+    /// dealers
+    pub(crate) fn with_sharings<R>(
+        index: usize,
+        threshold: usize,
+        parties: usize,
+        sharings: Vec<Sharing>,
+        rng: &mut R,
+    ) -> Result<Self, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if threshold == 0
+            || threshold > parties
+            || index == 0
+            || index > parties
+            || sharings.is_empty()
+            || sharings.iter().any(|sharing| sharing.coefficients == 0)
+        {
+            return Err(SpsEqSignatureError::InvalidParameters);
+        }
+
+        let mut random_polynomials = || -> Vec<Vec<E::Fr>> {
+            sharings
+                .iter()
+                .map(|sharing| {
+                    (0..sharing.coefficients)
+                        .map(|m| {
+                            if m == 0 && sharing.zero_constant {
+                                E::Fr::zero()
+                            } else {
+                                E::Fr::rand(rng)
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let polynomials = random_polynomials();
+        let blinding_polynomials = random_polynomials();
+        Ok(Participant {
+            index,
+            threshold,
+            parties,
+            sharings,
+            blinding_generator: blinding_generator::<P::Key>(),
+            polynomials,
+            blinding_polynomials,
+            commitments: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            blinding_shares: BTreeMap::new(),
+            complaints: BTreeSet::new(),
+            answered: BTreeSet::new(),
+            qualified: Vec::new(),
+            feldman_commitments: BTreeMap::new(),
+            disqualified: BTreeSet::new(),
+            revealed: BTreeMap::new(),
+        })
+    }
+
+    /// First round: returns the broadcast Pedersen commitments and the shares for the other
+    /// parties
+    pub fn deal(&mut self) -> Vec<Envelope<E, P>> {
+        let generator = P::Key::prime_subgroup_generator();
+        let commitments: Vec<Vec<P::Key>> = self
+            .polynomials
+            .iter()
+            .zip(&self.blinding_polynomials)
+            .map(|(coefficients, blindings)| {
+                coefficients
+                    .iter()
+                    .zip(blindings)
+                    .map(|(coefficient, blinding)| {
+                        generator.mul(coefficient.into_repr())
+                            + self.blinding_generator.mul(blinding.into_repr())
+                    })
+                    .collect()
+            })
+            .collect();
+        self.commitments.insert(self.index, commitments.clone());
+        self.shares.insert(self.index, self.shares_for(self.index));
+        self.blinding_shares
+            .insert(self.index, self.blinding_shares_for(self.index));
+
+        let mut outgoing = vec![Envelope {
+            sender: self.index,
+            recipient: None,
+            message: DkgMessage::Commitments(commitments),
+        }];
+        outgoing.extend(
+            (1..=self.parties)
+                .filter(|&recipient| recipient != self.index)
+                .map(|recipient| Envelope {
+                    sender: self.index,
+                    recipient: Some(recipient),
+                    message: DkgMessage::Shares(
+                        self.shares_for(recipient),
+                        self.blinding_shares_for(recipient),
+                    ),
+                }),
+        );
+        outgoing
+    }
+
+    /// Second round: records the commitments and shares of the other dealers, and returns the
+    /// broadcast complaints against the dealers whose shares are missing or invalid
+    pub fn complain(&mut self, incoming: Vec<Envelope<E, P>>) -> Vec<Envelope<E, P>> {
+        for envelope in incoming {
+            if envelope.sender == self.index || envelope.sender > self.parties {
+                continue;
+            }
+            match envelope.message {
+                DkgMessage::Commitments(commitments)
+                    if envelope.recipient.is_none() && self.is_well_formed(&commitments) =>
+                {
+                    self.commitments.insert(envelope.sender, commitments);
+                }
+                DkgMessage::Shares(shares, blinding_shares)
+                    if envelope.recipient == Some(self.index) =>
+                {
+                    self.shares.insert(envelope.sender, shares);
+                    self.blinding_shares
+                        .insert(envelope.sender, blinding_shares);
+                }
+                _ => {}
+            }
+        }
+
+        let dealers: Vec<usize> = self.commitments.keys().copied().collect();
+        let mut outgoing = Vec::new();
+        for dealer in dealers {
+            let valid = match (self.shares.get(&dealer), self.blinding_shares.get(&dealer)) {
+                (Some(shares), Some(blinding_shares)) => {
+                    self.check_shares(dealer, self.index, shares, blinding_shares)
+                }
+                _ => false,
+            };
+            if !valid {
+                self.shares.remove(&dealer);
+                self.blinding_shares.remove(&dealer);
+                self.complaints.insert((dealer, self.index));
+                outgoing.push(Envelope {
+                    sender: self.index,
+                    recipient: None,
+                    message: DkgMessage::Complaint(dealer),
+                });
+            }
+        }
+        outgoing
+    }
+
+    /// Third round: records the complaints, and returns the broadcast answers to the
+    /// complaints against the party
+    pub fn respond(&mut self, incoming: Vec<Envelope<E, P>>) -> Vec<Envelope<E, P>> {
+        for envelope in incoming {
+            if let (None, DkgMessage::Complaint(dealer)) = (envelope.recipient, &envelope.message) {
+                if self.commitments.contains_key(dealer) && envelope.sender <= self.parties {
+                    self.complaints.insert((*dealer, envelope.sender));
+                }
+            }
+        }
+
+        let accusers: Vec<usize> = self
+            .complaints
+            .iter()
+            .filter(|(dealer, _)| *dealer == self.index)
+            .map(|&(_, accuser)| accuser)
+            .collect();
+        accusers
+            .into_iter()
+            .map(|accuser| {
+                self.answered.insert((self.index, accuser));
+                Envelope {
+                    sender: self.index,
+                    recipient: None,
+                    message: DkgMessage::Response(
+                        accuser,
+                        self.shares_for(accuser),
+                        self.blinding_shares_for(accuser),
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Fourth round: checks the answers to the complaints, which determine the qualified
+    /// dealers, and returns the broadcast Feldman commitments of the party if it is qualified
+    pub fn extract(&mut self, incoming: Vec<Envelope<E, P>>) -> Vec<Envelope<E, P>> {
+        for envelope in incoming {
+            if let (None, DkgMessage::Response(accuser, shares, blinding_shares)) =
+                (envelope.recipient, &envelope.message)
+            {
+                let complaint = (envelope.sender, *accuser);
+                if self.complaints.contains(&complaint)
+                    && self.check_shares(envelope.sender, *accuser, shares, blinding_shares)
+                {
+                    self.answered.insert(complaint);
+                    if *accuser == self.index {
+                        self.shares.insert(envelope.sender, shares.clone());
+                        self.blinding_shares
+                            .insert(envelope.sender, blinding_shares.clone());
+                    }
+                }
+            }
+        }
+
+        self.qualified = self
+            .commitments
+            .keys()
+            .copied()
+            .filter(|dealer| {
+                self.complaints
+                    .iter()
+                    .filter(|(accused, _)| accused == dealer)
+                    .all(|complaint| self.answered.contains(complaint))
+            })
+            .collect();
+        if !self.qualified.contains(&self.index) {
+            return Vec::new();
+        }
+
+        let commitments = feldman_commitments::<E, P>(&self.polynomials);
+        self.feldman_commitments
+            .insert(self.index, commitments.clone());
+        vec![Envelope {
+            sender: self.index,
+            recipient: None,
+            message: DkgMessage::FeldmanCommitments(commitments),
+        }]
+    }
+
+    /// Fifth round: records the Feldman commitments of the qualified dealers, and returns the
+    /// broadcast accusations against the dealers whose Feldman commitments do not match the
+    /// shares of the party
+    pub fn accuse(&mut self, incoming: Vec<Envelope<E, P>>) -> Vec<Envelope<E, P>> {
+        for envelope in incoming {
+            if let (None, DkgMessage::FeldmanCommitments(commitments)) =
+                (envelope.recipient, envelope.message)
+            {
+                if envelope.sender != self.index
+                    && self.qualified.contains(&envelope.sender)
+                    && self.is_well_formed(&commitments)
+                {
+                    self.feldman_commitments
+                        .insert(envelope.sender, commitments);
+                }
+            }
+        }
+
+        let mut outgoing = Vec::new();
+        for dealer in self.qualified.clone() {
+            if !self.feldman_commitments.contains_key(&dealer) {
+                self.disqualified.insert(dealer);
+                continue;
+            }
+            // The shares of a qualified dealer are valid, or were revealed in its answer
+            if let (Some(shares), Some(blinding_shares)) =
+                (self.shares.get(&dealer), self.blinding_shares.get(&dealer))
+            {
+                if !self.check_feldman_shares(dealer, self.index, shares) {
+                    outgoing.push(Envelope {
+                        sender: self.index,
+                        recipient: None,
+                        message: DkgMessage::Accusation(
+                            dealer,
+                            shares.clone(),
+                            blinding_shares.clone(),
+                        ),
+                    });
+                    self.disqualified.insert(dealer);
+                }
+            }
+        }
+        outgoing
+    }
+
+    /// Sixth round: checks the accusations, and returns the broadcast shares of the party from
+    /// the dealers disqualified from extraction
+    pub fn reveal(&mut self, incoming: Vec<Envelope<E, P>>) -> Vec<Envelope<E, P>> {
+        for envelope in incoming {
+            if let (None, DkgMessage::Accusation(dealer, shares, blinding_shares)) =
+                (envelope.recipient, &envelope.message)
+            {
+                if envelope.sender <= self.parties
+                    && self.feldman_commitments.contains_key(dealer)
+                    && self.check_shares(*dealer, envelope.sender, shares, blinding_shares)
+                    && !self.check_feldman_shares(*dealer, envelope.sender, shares)
+                {
+                    self.disqualified.insert(*dealer);
+                }
+            }
+        }
+
+        let mut outgoing = Vec::new();
+        for &dealer in &self.disqualified {
+            if let (Some(shares), Some(blinding_shares)) =
+                (self.shares.get(&dealer), self.blinding_shares.get(&dealer))
+            {
+                self.revealed
+                    .entry(dealer)
+                    .or_default()
+                    .insert(self.index, shares.clone());
+                outgoing.push(Envelope {
+                    sender: self.index,
+                    recipient: None,
+                    message: DkgMessage::Reveal(dealer, shares.clone(), blinding_shares.clone()),
+                });
+            }
+        }
+        outgoing
+    }
+
+    /// Reconstructs the polynomials of the dealers disqualified from extraction, and computes
+    /// the shares of the party and the joint public key over the qualified dealers. The
+    /// function fails if a component of the joint public key is the identity or repeats
+    /// another one.
+    pub fn finish(
+        self,
+        incoming: Vec<Envelope<E, P>>,
+    ) -> Result<DkgOutput<E, P>, SpsEqSignatureError> {
+        let parties = self.parties;
+        let sharing = self.finish_sharing(incoming)?;
+        Ok(DkgOutput {
+            key_share: KeyShare::new(sharing.index, sharing.shares),
+            threshold_key: ThresholdKey::from_commitments(&sharing.commitments, parties)?,
+            qualified: sharing.qualified,
+        })
+    }
+
+    /// Runs the six rounds of the key generation over `transport`
+    pub fn run<T>(mut self, transport: &mut T) -> Result<DkgOutput<E, P>, SpsEqSignatureError>
+    where
+        T: Transport<E, P>,
+    {
+        let incoming = self.exchange_rounds(transport)?;
+        self.finish(incoming)
+    }
+
+    /// Index of the party, starting at 1
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Exchanges the messages of the six rounds over `transport`, returning the messages of
+    /// the last round
+    pub(crate) fn exchange_rounds<T>(
+        &mut self,
+        transport: &mut T,
+    ) -> Result<Vec<Envelope<E, P>>, SpsEqSignatureError>
+    where
+        T: Transport<E, P>,
+    {
+        let incoming = transport.exchange(self.deal())?;
+        let incoming = transport.exchange(self.complain(incoming))?;
+        let incoming = transport.exchange(self.respond(incoming))?;
+        let incoming = transport.exchange(self.extract(incoming))?;
+        let incoming = transport.exchange(self.accuse(incoming))?;
+        transport.exchange(self.reveal(incoming))
+    }
+
+    /// Reconstructs the polynomials of the dealers disqualified from extraction, and sums the
+    /// shares of the party and the Feldman commitments over the qualified dealers
+    pub(crate) fn finish_sharing(
+        mut self,
+        incoming: Vec<Envelope<E, P>>,
+    ) -> Result<JointSharing<E, P>, SpsEqSignatureError> {
+        if self.qualified.len() < self.threshold {
+            return Err(SpsEqSignatureError::NotEnoughShares);
+        }
+
+        for envelope in incoming {
+            if let (None, DkgMessage::Reveal(dealer, shares, blinding_shares)) =
+                (envelope.recipient, envelope.message)
+            {
+                if self.disqualified.contains(&dealer)
+                    && envelope.sender <= self.parties
+                    && self.check_shares(dealer, envelope.sender, &shares, &blinding_shares)
+                {
+                    self.revealed
+                        .entry(dealer)
+                        .or_default()
+                        .insert(envelope.sender, shares);
+                }
+            }
+        }
+
+        // The revealed shares match the Pedersen commitments, so they lie on the polynomials
+        // of the dealer, and as many shares as coefficients determine each polynomial
+        for &dealer in &self.disqualified {
+            let revealed = self
+                .revealed
+                .get(&dealer)
+                .ok_or(SpsEqSignatureError::NotEnoughShares)?;
+            let mut polynomials = Vec::with_capacity(self.sharings.len());
+            for (i, sharing) in self.sharings.iter().enumerate() {
+                if revealed.len() < sharing.coefficients {
+                    return Err(SpsEqSignatureError::NotEnoughShares);
+                }
+                let points: Vec<(usize, E::Fr)> = revealed
+                    .iter()
+                    .take(sharing.coefficients)
+                    .map(|(&party, shares)| (party, shares[i]))
+                    .collect();
+                polynomials.push(interpolate_polynomial(&points));
+            }
+            self.feldman_commitments
+                .insert(dealer, feldman_commitments::<E, P>(&polynomials));
+        }
+
+        let mut shares = vec![E::Fr::zero(); self.sharings.len()];
+        let mut commitments: Vec<Vec<P::Key>> = self
+            .sharings
+            .iter()
+            .map(|sharing| vec![P::Key::zero(); sharing.coefficients])
+            .collect();
+        for dealer in &self.qualified {
+            let dealer_shares = self
+                .shares
+                .get(dealer)
+                .ok_or(SpsEqSignatureError::NotEnoughShares)?;
+            for (i, dealer_commitments) in self.feldman_commitments[dealer].iter().enumerate() {
+                shares[i] += dealer_shares[i];
+                for (sum, commitment) in commitments[i].iter_mut().zip(dealer_commitments) {
+                    *sum += commitment;
+                }
+            }
+        }
+
+        Ok(JointSharing {
+            index: self.index,
+            shares,
+            commitments,
+            qualified: self.qualified.clone(),
+        })
+    }
+
+    fn shares_for(&self, recipient: usize) -> Vec<E::Fr> {
+        self.polynomials
+            .iter()
+            .map(|coefficients| evaluate_polynomial(coefficients, recipient))
+            .collect()
+    }
+
+    fn blinding_shares_for(&self, recipient: usize) -> Vec<E::Fr> {
+        self.blinding_polynomials
+            .iter()
+            .map(|coefficients| evaluate_polynomial(coefficients, recipient))
+            .collect()
+    }
+
+    /// Checks that Pedersen or Feldman commitments have the shape of the sharings, with
+    /// identity constant terms for the sharings of zero
+    fn is_well_formed(&self, commitments: &[Vec<P::Key>]) -> bool {
+        commitments.len() == self.sharings.len()
+            && commitments
+                .iter()
+                .zip(&self.sharings)
+                .all(|(coefficients, sharing)| {
+                    coefficients.len() == sharing.coefficients
+                        && (!sharing.zero_constant || coefficients[0].is_zero())
+                })
+    }
+
+    /// Checks `a_{dealer, i}(recipient) g2 + b_{dealer, i}(recipient) h2 = sum_m recipient^m
+    /// C_{dealer, i, m}` for every secret
+    fn check_shares(
+        &self,
+        dealer: usize,
+        recipient: usize,
+        shares: &[E::Fr],
+        blinding_shares: &[E::Fr],
+    ) -> bool {
+        let commitments = match self.commitments.get(&dealer) {
+            Some(commitments) => commitments,
+            None => return false,
+        };
+        if shares.len() != self.sharings.len() || blinding_shares.len() != self.sharings.len() {
+            return false;
+        }
+
+        let generator = P::Key::prime_subgroup_generator();
+        commitments
+            .iter()
+            .zip(shares.iter().zip(blinding_shares))
+            .all(|(coefficients, (share, blinding_share))| {
+                generator.mul(share.into_repr())
+                    + self.blinding_generator.mul(blinding_share.into_repr())
+                    == evaluate_commitments(coefficients, recipient)
+            })
+    }
+
+    /// Checks `a_{dealer, i}(recipient) g2 = sum_m recipient^m A_{dealer, i, m}` for every
+    /// secret
+    fn check_feldman_shares(&self, dealer: usize, recipient: usize, shares: &[E::Fr]) -> bool {
+        let commitments = &self.feldman_commitments[&dealer];
+        let generator = P::Key::prime_subgroup_generator();
+        commitments.iter().zip(shares).all(|(coefficients, share)| {
+            generator.mul(share.into_repr()) == evaluate_commitments(coefficients, recipient)
+        })
+    }
+}
+
+/// Feldman commitments `a_{i, m} g2` to the coefficients of the polynomials `a_i`
+fn feldman_commitments<E: PairingEngine, P: Placement<E>>(
+    polynomials: &[Vec<E::Fr>],
+) -> Vec<Vec<P::Key>> {
+    let generator = P::Key::prime_subgroup_generator();
+    polynomials
+        .iter()
+        .map(|coefficients| {
+            coefficients
+                .iter()
+                .map(|coefficient| generator.mul(coefficient.into_repr()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Generator `h` of the Pedersen commitments, derived from a fixed label by try-and-increment
+/// so that nobody knows its discrete logarithm in base `g`
+fn blinding_generator<G: ProjectiveCurve>() -> G {
+    let mut transcript = Transcript::new(b"sps-eq-dkg-pedersen-generator");
+    let mut bytes = vec![0u8; G::Affine::zero().serialized_size()];
+    loop {
+        transcript.challenge_bytes(b"candidate", &mut bytes);
+        if let Some(point) = G::Affine::from_random_bytes(&bytes) {
+            let point = point.mul_by_cofactor_to_projective();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> fmt::Debug for Participant<E, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Participant")
+            .field("index", &self.index)
+            .field("threshold", &self.threshold)
+            .field("parties", &self.parties)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> Drop for Participant<E, P> {
+    fn drop(&mut self) {
+        self.polynomials.iter_mut().for_each(Zeroize::zeroize);
+        self.blinding_polynomials
+            .iter_mut()
+            .for_each(Zeroize::zeroize);
+        self.shares.values_mut().for_each(Zeroize::zeroize);
+        self.blinding_shares.values_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> KeyShare<E, P> {
+    pub(crate) fn new(index: usize, shares: Vec<E::Fr>) -> Self {
+        KeyShare {
+            index,
+            shares,
+            placement: PhantomData,
+        }
+    }
+
+    /// Returns the shares of the secret keys, one per key
+    pub fn shares(&self) -> &[E::Fr] {
+        &self.shares
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> fmt::Debug for KeyShare<E, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> Drop for KeyShare<E, P> {
+    fn drop(&mut self) {
+        self.shares.zeroize();
+    }
+}
+
+/// Network connecting parties in the same process, for instance in separate threads
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct InProcessNetwork<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    state: Mutex<NetworkState<E, P>>,
+    round_complete: Condvar,
+}
+
+/// Endpoint of a party on an [`InProcessNetwork`]. Dropping it makes the party leave the
+/// network, so that the other parties stop waiting for its messages.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct InProcessTransport<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    index: usize,
+    network: Arc<InProcessNetwork<E, P>>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct NetworkState<E: PairingEngine, P: Placement<E>> {
+    connected: Vec<bool>,
+    round: usize,
+    sent: BTreeSet<usize>,
+    pending: Vec<Envelope<E, P>>,
+    mailboxes: Vec<Vec<Envelope<E, P>>>,
+}
+
+#[cfg(feature = "std")]
+impl<E: PairingEngine, P: Placement<E>> InProcessNetwork<E, P> {
+    /// Creates a network for `parties` parties, returning the endpoint of each party by
+    /// increasing index
+    pub fn connect(parties: usize) -> Vec<InProcessTransport<E, P>> {
+        let network = Arc::new(InProcessNetwork {
+            state: Mutex::new(NetworkState {
+                connected: vec![true; parties],
+                round: 0,
+                sent: BTreeSet::new(),
+                pending: Vec::new(),
+                mailboxes: vec![Vec::new(); parties],
+            }),
+            round_complete: Condvar::new(),
+        });
+
+        (1..=parties)
+            .map(|index| InProcessTransport {
+                index,
+                network: network.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: PairingEngine, P: Placement<E>> NetworkState<E, P> {
+    /// Delivers the pending messages once every connected party has sent its messages
+    fn try_complete_round(&mut self) -> bool {
+        let connected = self
+            .connected
+            .iter()
+            .filter(|&&connected| connected)
+            .count();
+        if self.sent.len() < connected || self.sent.is_empty() {
+            return false;
+        }
+
+        for envelope in self.pending.drain(..) {
+            for (position, mailbox) in self.mailboxes.iter_mut().enumerate() {
+                let recipient = position + 1;
+                let addressed = match envelope.recipient {
+                    Some(index) => index == recipient,
+                    None => envelope.sender != recipient,
+                };
+                if addressed && self.connected[position] {
+                    mailbox.push(envelope.clone());
+                }
+            }
+        }
+        self.sent.clear();
+        self.round += 1;
+        true
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: PairingEngine, P: Placement<E>> Transport<E, P> for InProcessTransport<E, P> {
+    fn exchange(
+        &mut self,
+        outgoing: Vec<Envelope<E, P>>,
+    ) -> Result<Vec<Envelope<E, P>>, SpsEqSignatureError> {
+        let mut state = self
+            .network
+            .state
+            .lock()
+            .expect("A party panicked while holding the lock");
+        // Parties cannot send messages on behalf of others
+        state.pending.extend(
+            outgoing
+                .into_iter()
+                .filter(|envelope| envelope.sender == self.index),
+        );
+        state.sent.insert(self.index);
+
+        let round = state.round;
+        if state.try_complete_round() {
+            self.network.round_complete.notify_all();
+        }
+        while state.round == round {
+            state = self
+                .network
+                .round_complete
+                .wait(state)
+                .expect("A party panicked while holding the lock");
+        }
+
+        Ok(core::mem::take(&mut state.mailboxes[self.index - 1]))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: PairingEngine, P: Placement<E>> Drop for InProcessTransport<E, P> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.network.state.lock() {
+            state.connected[self.index - 1] = false;
+            state.sent.remove(&self.index);
+            if state.try_complete_round() {
+                self.network.round_complete.notify_all();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::SigningKey;
+    use crate::utils::lagrange_coefficient;
+    use crate::verify::PublicKey;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1, G2Projective as G2};
+    use rand::thread_rng;
+    #[cfg(feature = "std")]
+    use std::thread;
+
+    type Party = Participant<Bls12_381>;
+    /// Round after dealing, from the messages of the previous round to those of the party
+    type Round = fn(&mut Party, Vec<Envelope<Bls12_381>>) -> Vec<Envelope<Bls12_381>>;
+
+    /// Runs the rounds of the parties in lockstep, letting `tamper` modify the messages of
+    /// each round before they are delivered, and finishes every party
+    fn run_in_lockstep<F>(parties: Vec<Party>, tamper: F) -> Vec<DkgOutput<Bls12_381>>
+    where
+        F: FnMut(usize, &mut Vec<Envelope<Bls12_381>>),
+    {
+        finish_in_lockstep(parties, tamper)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    /// Runs the rounds of the parties in lockstep, and returns the outcome of every party
+    fn finish_in_lockstep<F>(
+        mut parties: Vec<Party>,
+        mut tamper: F,
+    ) -> Vec<Result<DkgOutput<Bls12_381>, SpsEqSignatureError>>
+    where
+        F: FnMut(usize, &mut Vec<Envelope<Bls12_381>>),
+    {
+        let deliver = |outgoing: &[Envelope<Bls12_381>], index: usize| -> Vec<_> {
+            outgoing
+                .iter()
+                .filter(|envelope| {
+                    envelope.sender != index && envelope.recipient.unwrap_or(index) == index
+                })
+                .cloned()
+                .collect()
+        };
+
+        let rounds: [Round; 5] = [
+            Party::complain,
+            Party::respond,
+            Party::extract,
+            Party::accuse,
+            Party::reveal,
+        ];
+        let mut outgoing: Vec<_> = parties.iter_mut().flat_map(|party| party.deal()).collect();
+        tamper(0, &mut outgoing);
+        for (round, next_round) in rounds.iter().enumerate() {
+            let mut next = Vec::new();
+            for party in parties.iter_mut() {
+                next.extend(next_round(party, deliver(&outgoing, party.index)));
+            }
+            outgoing = next;
+            tamper(round + 1, &mut outgoing);
+        }
+
+        parties
+            .into_iter()
+            .map(|party| {
+                let incoming = deliver(&outgoing, party.index);
+                party.finish(incoming)
+            })
+            .collect()
+    }
+
+    fn new_parties(threshold: usize, parties: usize, capacity: usize) -> Vec<Party> {
+        let rng = &mut thread_rng();
+        (1..=parties)
+            .map(|index| Participant::new(index, threshold, parties, capacity, rng).unwrap())
+            .collect()
+    }
+
+    /// Reconstructs the signing key from the shares of the given parties
+    fn reconstruct(outputs: &[&DkgOutput<Bls12_381>]) -> SigningKey<Bls12_381> {
+        let indices: Vec<usize> = outputs
+            .iter()
+            .map(|output| output.key_share.index)
+            .collect();
+        let capacity = outputs[0].key_share.shares().len();
+        let secret_keys = (0..capacity)
+            .map(|i| {
+                outputs
+                    .iter()
+                    .map(|output| {
+                        lagrange_coefficient::<Fr>(&indices, output.key_share.index)
+                            * output.key_share.shares()[i]
+                    })
+                    .sum()
+            })
+            .collect();
+        SigningKey::from(secret_keys).unwrap()
+    }
+
+    fn assert_agree(outputs: &[&DkgOutput<Bls12_381>], qualified: &[usize]) {
+        for output in outputs {
+            assert_eq!(output.qualified, qualified);
+            assert_eq!(output.threshold_key, outputs[0].threshold_key);
+        }
+    }
+
+    #[test]
+    fn test_dkg() {
+        let outputs = run_in_lockstep(new_parties(3, 5, 2), |_, _| {});
+        assert_agree(&outputs.iter().collect::<Vec<_>>(), &[1, 2, 3, 4, 5]);
+
+        // Any 3 parties hold shares of the key of the joint public key
+        let rng = &mut thread_rng();
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        for subset in [[0usize, 1, 2], [4, 1, 3]].iter() {
+            let outputs: Vec<_> = subset.iter().map(|&j| &outputs[j]).collect();
+            let signing_key = reconstruct(&outputs);
+            assert_eq!(
+                PublicKey::from(&signing_key),
+                outputs[0].threshold_key.public_key
+            );
+
+            let signature = signing_key.sign(&message, rng).unwrap();
+            assert!(outputs[0]
+                .threshold_key
+                .public_key
+                .verify(&message, &signature)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_debug_output_leaves_secrets_out() {
+        let party = new_parties(2, 3, 1).remove(0);
+        let coefficient = format!("{:?}", party.polynomials[0][1]);
+        assert!(!format!("{:?}", party).contains(&coefficient));
+
+        let outputs = run_in_lockstep(new_parties(2, 3, 1), |_, _| {});
+        let share = format!("{:?}", outputs[0].key_share.shares()[0]);
+        let output = format!("{:?}", outputs[0]);
+        assert!(output.contains("KeyShare { index: 1, .. }"));
+        assert!(!output.contains(&share));
+    }
+
+    #[test]
+    fn test_dkg_with_bad_shares() {
+        // Party 2 sends bad shares to party 4 and does not answer the complaint
+        let outputs = run_in_lockstep(new_parties(2, 4, 2), |round, outgoing| {
+            for envelope in outgoing.iter_mut() {
+                if envelope.sender == 2 {
+                    match (&mut envelope.message, round) {
+                        (DkgMessage::Shares(shares, _), 0) if envelope.recipient == Some(4) => {
+                            shares[1] += Fr::from(1u64)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if round == 2 {
+                outgoing.retain(|envelope| envelope.sender != 2);
+            }
+        });
+        // The honest parties leave party 2 out
+        assert_agree(&[&outputs[0], &outputs[2], &outputs[3]], &[1, 3, 4]);
+        let signing_key = reconstruct(&[&outputs[0], &outputs[3]]);
+        assert_eq!(
+            PublicKey::from(&signing_key),
+            outputs[0].threshold_key.public_key
+        );
+
+        // Party 3 sends bad shares to party 1, but reveals valid ones when it complains
+        let outputs = run_in_lockstep(new_parties(2, 4, 2), |round, outgoing| {
+            for envelope in outgoing.iter_mut() {
+                if let (DkgMessage::Shares(shares, _), 0, 3, Some(1)) = (
+                    &mut envelope.message,
+                    round,
+                    envelope.sender,
+                    envelope.recipient,
+                ) {
+                    shares[0] += Fr::from(1u64);
+                }
+            }
+        });
+        assert_agree(&outputs.iter().collect::<Vec<_>>(), &[1, 2, 3, 4]);
+        let signing_key = reconstruct(&[&outputs[0], &outputs[2]]);
+        assert_eq!(
+            PublicKey::from(&signing_key),
+            outputs[0].threshold_key.public_key
+        );
+    }
+
+    #[test]
+    fn test_dkg_with_inconsistent_feldman_commitments() {
+        // Party 2 changes the constant term of its Feldman commitments once the qualified
+        // dealers are known, or withholds them. Its polynomials are reconstructed, so that its
+        // contribution to the key stays the one it committed to.
+        for withhold in [false, true].iter() {
+            let outputs = run_in_lockstep(new_parties(2, 4, 2), |round, outgoing| {
+                if round != 3 {
+                    return;
+                }
+                if *withhold {
+                    outgoing.retain(|envelope| envelope.sender != 2);
+                }
+                for envelope in outgoing.iter_mut() {
+                    if let (DkgMessage::FeldmanCommitments(commitments), 2) =
+                        (&mut envelope.message, envelope.sender)
+                    {
+                        commitments[0][0] += G2::prime_subgroup_generator();
+                    }
+                }
+            });
+            assert_agree(&[&outputs[0], &outputs[2], &outputs[3]], &[1, 2, 3, 4]);
+            let signing_key = reconstruct(&[&outputs[0], &outputs[2]]);
+            assert_eq!(
+                PublicKey::from(&signing_key),
+                outputs[0].threshold_key.public_key
+            );
+        }
+    }
+
+    #[test]
+    fn test_dkg_with_false_accusation() {
+        // Party 1 accuses party 3 with shares that do not match the Pedersen commitments
+        let mut reveals = 0;
+        let outputs = run_in_lockstep(new_parties(2, 3, 2), |round, outgoing| {
+            if round == 4 {
+                outgoing.push(Envelope {
+                    sender: 1,
+                    recipient: None,
+                    message: DkgMessage::Accusation(
+                        3,
+                        vec![Fr::from(1u64); 2],
+                        vec![Fr::from(2u64); 2],
+                    ),
+                });
+            }
+            if round == 5 {
+                reveals += outgoing.len();
+            }
+        });
+        // The accusation is ignored, and no share is revealed
+        assert_eq!(reveals, 0);
+        assert_agree(&outputs.iter().collect::<Vec<_>>(), &[1, 2, 3]);
+        let signing_key = reconstruct(&[&outputs[1], &outputs[2]]);
+        assert_eq!(
+            PublicKey::from(&signing_key),
+            outputs[0].threshold_key.public_key
+        );
+    }
+
+    #[test]
+    fn test_dkg_with_malformed_commitments() {
+        let outputs = run_in_lockstep(new_parties(2, 3, 2), |round, outgoing| {
+            for envelope in outgoing.iter_mut() {
+                if let (DkgMessage::Commitments(commitments), 0, 1) =
+                    (&mut envelope.message, round, envelope.sender)
+                {
+                    commitments.pop();
+                }
+            }
+        });
+        // Party 1 does not see its own commitments as malformed, the others leave it out
+        assert_eq!(outputs[1].qualified, vec![2, 3]);
+        assert_eq!(outputs[2].qualified, vec![2, 3]);
+        assert_eq!(outputs[1].threshold_key, outputs[2].threshold_key);
+    }
+
+    #[test]
+    fn test_dkg_with_invalid_public_key() {
+        // The dealers share the same polynomials for both secret keys
+        let mut parties = new_parties(2, 3, 2);
+        for party in parties.iter_mut() {
+            party.polynomials[1] = party.polynomials[0].clone();
+        }
+        for output in finish_in_lockstep(parties, |_, _| {}) {
+            assert_eq!(output.unwrap_err(), SpsEqSignatureError::InvalidPublicKey);
+        }
+
+        // The constant terms of the dealers sum to zero
+        let mut parties = new_parties(2, 3, 2);
+        let sum: Fr = parties[..2]
+            .iter()
+            .map(|party| party.polynomials[0][0])
+            .sum();
+        parties[2].polynomials[0][0] = -sum;
+        for output in finish_in_lockstep(parties, |_, _| {}) {
+            assert_eq!(output.unwrap_err(), SpsEqSignatureError::InvalidPublicKey);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dkg_over_in_process_network() {
+        let parties = 5;
+        let threshold = 3;
+        let transports = InProcessNetwork::<Bls12_381>::connect(parties);
+
+        let handles: Vec<_> = transports
+            .into_iter()
+            .map(|mut transport| {
+                thread::spawn(move || {
+                    // Party 5 drops out before dealing
+                    if transport.index == 5 {
+                        return None;
+                    }
+                    let participant = Participant::<Bls12_381>::new(
+                        transport.index,
+                        threshold,
+                        parties,
+                        2,
+                        &mut thread_rng(),
+                    )
+                    .unwrap();
+                    Some(participant.run(&mut transport).unwrap())
+                })
+            })
+            .collect();
+        let outputs: Vec<_> = handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .collect();
+
+        assert_eq!(outputs.len(), 4);
+        assert_agree(&outputs.iter().collect::<Vec<_>>(), &[1, 2, 3, 4]);
+        let signing_key = reconstruct(&[&outputs[0], &outputs[2], &outputs[3]]);
+        assert_eq!(
+            PublicKey::from(&signing_key),
+            outputs[0].threshold_key.public_key
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_threshold_signing_over_in_process_network() {
+        let parties = 4;
+        let threshold = 2;
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(&mut thread_rng())).collect();
+        let transports = InProcessNetwork::<Bls12_381>::connect(parties);
+
+        // Every party generates the key, then a presignature, and signs with its key share
+        let handles: Vec<_> = transports
+            .into_iter()
+            .map(|mut transport| {
+                let message = message.clone();
+                thread::spawn(move || {
+                    let rng = &mut thread_rng();
+                    let output = Participant::new(transport.index, threshold, parties, 3, rng)
+                        .unwrap()
+                        .run(&mut transport)
+                        .unwrap();
+                    let (presignature, share) =
+                        Participant::new_presigner(transport.index, &output.threshold_key, rng)
+                            .unwrap()
+                            .run_presignature(&mut transport)
+                            .unwrap();
+                    let partial = share.sign(&output.key_share, &message).unwrap();
+                    (output.threshold_key, presignature, partial)
+                })
+            })
+            .collect();
+        let results: Vec<_> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        let (key, presignature, _) = &results[0];
+        let partials: Vec<_> = results
+            .iter()
+            .map(|(_, _, partial)| partial.clone())
+            .collect();
+        let signature = presignature.combine(key, &message, &partials).unwrap();
+        assert!(key.public_key.verify(&message, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let rng = &mut thread_rng();
+        assert_eq!(
+            Party::new(1, 0, 3, 2, rng).unwrap_err(),
+            SpsEqSignatureError::InvalidParameters
+        );
+        assert_eq!(
+            Party::new(4, 2, 3, 2, rng).unwrap_err(),
+            SpsEqSignatureError::InvalidParameters
+        );
+    }
+}
@@ -1,12 +1,52 @@
-use std::fmt;
-use std::fmt::Display;
+use ark_serialize::SerializationError;
+use core::fmt;
+use core::fmt::Display;
 
+/// Errors returned by the signing, verification and (de)serialization procedures
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum SpsEqSignatureError {
+    /// The capacity of a key does not match the length of a message
     UnmatchedCapacity,
+    /// The signature does not verify
     InvalidSignature,
+    /// The secret key cannot be built from the given scalars
     InvalidSecretKeyVector,
+    /// Writing to the output failed
     IoErrorWrite,
+    /// The input ends before the encoding is complete, or is shorter than its length prefix
+    TruncatedInput,
+    /// The encoding was produced by an unsupported version of the wire format
+    UnsupportedVersion,
+    /// The input continues after the end of the encoding
+    TrailingBytes,
+    /// A point encoding is malformed, not canonical, or does not lie on the curve
+    InvalidPointEncoding,
+    /// A point lies on the curve but outside of the prime-order subgroup
+    PointNotInSubgroup,
+    /// A point is the identity where a non-trivial group element is required
+    IdentityPoint,
+    /// A scalar encoding is malformed or not canonical
+    InvalidScalarEncoding,
+    /// A message component is the identity
+    IdentityMessageComponent,
+    /// A signature point is the identity
+    IdentitySignatureComponent,
+    /// The randomness used to change the representation is zero
+    InvalidRandomness,
+    /// The tag is zero, or a tagged message does not carry the expected tag
+    InvalidTag,
+    /// A zero-knowledge proof does not verify
+    InvalidProof,
+    /// A token was shown twice
+    DoubleSpending,
+    /// A disclosed attribute is not part of the committed set
+    AttributeNotInSet,
+    /// The public parameters are not well formed
+    InvalidParameters,
+    /// Fewer valid shares than the threshold were provided
+    NotEnoughShares,
+    /// A component of a jointly generated public key is the identity or repeats another one
+    InvalidPublicKey,
 }
 
 impl Display for SpsEqSignatureError {
@@ -18,6 +58,50 @@ impl Display for SpsEqSignatureError {
                 write!(f, "Failed to generate a secret key from the given array")
             }
             SpsEqSignatureError::IoErrorWrite => write!(f, "Error writing in the IO stream"),
+            SpsEqSignatureError::TruncatedInput => write!(f, "The input is truncated"),
+            SpsEqSignatureError::UnsupportedVersion => {
+                write!(f, "Unsupported serialization version")
+            }
+            SpsEqSignatureError::TrailingBytes => write!(f, "Unexpected bytes after the encoding"),
+            SpsEqSignatureError::InvalidPointEncoding => write!(f, "Invalid point encoding"),
+            SpsEqSignatureError::PointNotInSubgroup => {
+                write!(f, "The point is not in the prime-order subgroup")
+            }
+            SpsEqSignatureError::IdentityPoint => write!(f, "Unexpected identity point"),
+            SpsEqSignatureError::InvalidScalarEncoding => write!(f, "Invalid scalar encoding"),
+            SpsEqSignatureError::IdentityMessageComponent => {
+                write!(f, "A message component is the identity")
+            }
+            SpsEqSignatureError::IdentitySignatureComponent => {
+                write!(f, "A signature component is the identity")
+            }
+            SpsEqSignatureError::InvalidRandomness => {
+                write!(f, "The randomness of the new representation is zero")
+            }
+            SpsEqSignatureError::InvalidTag => write!(f, "The tag is zero or does not match"),
+            SpsEqSignatureError::InvalidProof => write!(f, "The proof does not verify"),
+            SpsEqSignatureError::DoubleSpending => write!(f, "The token was already spent"),
+            SpsEqSignatureError::AttributeNotInSet => {
+                write!(f, "The attribute is not part of the set")
+            }
+            SpsEqSignatureError::InvalidParameters => write!(f, "Invalid public parameters"),
+            SpsEqSignatureError::NotEnoughShares => write!(f, "Not enough valid shares"),
+            SpsEqSignatureError::InvalidPublicKey => {
+                write!(f, "The public key has an identity or repeated component")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SpsEqSignatureError {}
+
+/// Errors of the canonical (de)serialization of the crate types
+impl From<SpsEqSignatureError> for SerializationError {
+    fn from(error: SpsEqSignatureError) -> Self {
+        match error {
+            SpsEqSignatureError::IoErrorWrite => SerializationError::NotEnoughSpace,
+            _ => SerializationError::InvalidData,
         }
     }
 }
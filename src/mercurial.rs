@@ -0,0 +1,144 @@
+//! Module describing mercurial signatures, as presented in the paper
+//! ["Delegatable Anonymous Credentials from Mercurial Signatures"][mercurial] by Elizabeth C.
+//! Crites and Anna Lysyanskaya.
+//!
+//! Mercurial signatures extend SPS-EQ with equivalence classes of keys. Messages
+//! $M\in(\mathbb{G}_1^*)^l$ and public keys $pk\in(\mathbb{G}_2^*)^l$ are both considered up to a
+//! scalar factor, and a signature can be moved to any representative of the message class and
+//! of the key class. Key generation, signing and verification are those of the SPS-EQ scheme:
+//! use [`SigningKey`](crate::sign::SigningKey), [`PublicKey`] and [`SpsEqSignature`] directly,
+//! and the [`convert`](crate::convert) module for $\texttt{ConvertSK}$, $\texttt{ConvertPK}$ and
+//! $\texttt{ConvertSig}$. This module only adds the change of representative:
+//!
+//! * $\texttt{ChangeRep}(pk, M, \sigma, \mu)$: pick $\psi\in_R\mathbb Z_p^*$ and return
+//!   $(M^\mu, \sigma')$ with $\sigma'\leftarrow(\psi\mu Z, Y_1^{\frac{1}{\psi}}, Y_2^{\frac{1}{\psi}})$.
+//!
+//! [`change_rep_with_key`] performs both changes at once, returning a fresh representative of
+//! the message, the signature and the public key.
+//!
+//! [mercurial]: https://eprint.iacr.org/2018/923.pdf
+
+use alloc::vec::Vec;
+use ark_ec::PairingEngine;
+use ark_ff::Zero;
+
+use crate::convert::convert_pk;
+use crate::errors::*;
+use crate::placement::*;
+use crate::sign::{Representation, SpsEqSignature};
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
+use rand::{CryptoRng, Rng};
+
+/// Representative of a key class, together with a message and a signature under that key
+pub type KeyedRepresentation<E, P = MessagesInG1> = (
+    PublicKey<E, P>,
+    Vec<<P as Placement<E>>::Message>,
+    SpsEqSignature<E, P>,
+);
+
+/// Changes the representative of the message class to `mu` times the message, and adapts the
+/// signature accordingly, returning the new signature and message. The signature is verified
+/// first, and `mu` must be nonzero.
+pub fn change_rep<E, P, R>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    signature: &SpsEqSignature<E, P>,
+    mu: E::Fr,
+    rng: &mut R,
+) -> Result<Representation<E, P>, SpsEqSignatureError>
+where
+    E: PairingEngine,
+    P: Placement<E>,
+    R: Rng + CryptoRng,
+{
+    public_key.verify(message, signature)?;
+
+    let psi = random_nonzero_scalar(rng);
+    signature.change_repr_with(message, mu, psi)
+}
+
+/// Changes the representatives of both the message class and the key class: the message is
+/// multiplied by `mu` and the public key by `rho`. Returns the new public key, message and
+/// signature, which verifies under the new public key. The signature is verified first, and
+/// `mu` and `rho` must be nonzero.
+pub fn change_rep_with_key<E, P, R>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    signature: &SpsEqSignature<E, P>,
+    mu: E::Fr,
+    rho: E::Fr,
+    rng: &mut R,
+) -> Result<KeyedRepresentation<E, P>, SpsEqSignatureError>
+where
+    E: PairingEngine,
+    P: Placement<E>,
+    R: Rng + CryptoRng,
+{
+    if rho.is_zero() {
+        return Err(SpsEqSignatureError::InvalidRandomness);
+    }
+    let (mut signature, message) = change_rep(public_key, message, signature, mu, rng)?;
+    signature.Z *= rho;
+
+    Ok((convert_pk(public_key, rho)?, message, signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::convert_sk;
+    use crate::sign::SigningKey;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_mercurial_signature() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+        assert!(pk.verify(&message, &signature).is_ok());
+
+        let mu = Fr::rand(rng);
+        let (new_signature, new_message) = change_rep(&pk, &message, &signature, mu, rng).unwrap();
+        assert!(pk.verify(&new_message, &new_signature).is_ok());
+        assert_eq!(new_message[0], message[0].mul(mu.into_repr()));
+
+        let rho = Fr::rand(rng);
+        let (new_pk, new_message, new_signature) =
+            change_rep_with_key(&pk, &message, &signature, mu, rho, rng).unwrap();
+        assert!(new_pk.verify(&new_message, &new_signature).is_ok());
+        assert!(pk.verify(&new_message, &new_signature).is_err());
+        assert_eq!(new_pk, PublicKey::from(&convert_sk(&sk, rho).unwrap()));
+    }
+
+    #[test]
+    fn test_change_rep_rejects_invalid_input() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(2, rng);
+        let pk = PublicKey::from(&sk);
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+
+        assert_eq!(
+            change_rep(&pk, &message, &signature, Fr::zero(), rng),
+            Err(SpsEqSignatureError::InvalidRandomness)
+        );
+        assert_eq!(
+            change_rep_with_key(&pk, &message, &signature, Fr::rand(rng), Fr::zero(), rng)
+                .unwrap_err(),
+            SpsEqSignatureError::InvalidRandomness
+        );
+
+        let different_message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        assert_eq!(
+            change_rep(&pk, &different_message, &signature, Fr::rand(rng), rng),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Module describing the placement of messages and keys in the source groups of the pairing.
+//!
+//! The scheme as described in the crate documentation signs messages in $\mathbb{G}_1$ under
+//! keys in $\mathbb{G}_2$, which is the default placement [`MessagesInG1`]. With
+//! [`MessagesInG2`] the groups are swapped: message components, $Z$ and $Y_1$ lie in
+//! $\mathbb{G}_2$, while public keys and $Y_2$ lie in $\mathbb{G}_1$.
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use core::fmt::Debug;
+
+/// Placement of the messages and keys of the scheme in the source groups of the pairing
+pub trait Placement<E: PairingEngine>:
+    Copy + Clone + Debug + Eq + PartialEq + Send + Sync + 'static
+{
+    /// Group of the message components and of the `Z` and `Y` signature points
+    type Message: ProjectiveCurve<ScalarField = E::Fr>;
+    /// Group of the public keys and of the `Yp` signature point
+    type Key: ProjectiveCurve<ScalarField = E::Fr>;
+    /// Point of the message group prepared for the Miller loop
+    type PreparedMessage: Clone + Debug;
+    /// Point of the key group prepared for the Miller loop
+    type PreparedKey: Clone + Debug;
+
+    /// Prepares a point of the message group for the Miller loop
+    fn prepare_message(point: MessageAffine<E, Self>) -> Self::PreparedMessage;
+
+    /// Prepares a point of the key group for the Miller loop
+    fn prepare_key(point: KeyAffine<E, Self>) -> Self::PreparedKey;
+
+    /// Arranges a message group point and a key group point as the arguments of the pairing
+    fn pair(
+        message: Self::PreparedMessage,
+        key: Self::PreparedKey,
+    ) -> (E::G1Prepared, E::G2Prepared);
+}
+
+/// Affine representation of the message group of a placement
+pub type MessageAffine<E, P> = <<P as Placement<E>>::Message as ProjectiveCurve>::Affine;
+
+/// Affine representation of the key group of a placement
+pub type KeyAffine<E, P> = <<P as Placement<E>>::Key as ProjectiveCurve>::Affine;
+
+/// Messages in G1 and keys in G2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessagesInG1;
+
+/// Messages in G2 and keys in G1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessagesInG2;
+
+impl<E: PairingEngine> Placement<E> for MessagesInG1 {
+    type Message = E::G1Projective;
+    type Key = E::G2Projective;
+    type PreparedMessage = E::G1Prepared;
+    type PreparedKey = E::G2Prepared;
+
+    fn prepare_message(point: E::G1Affine) -> E::G1Prepared {
+        point.into()
+    }
+
+    fn prepare_key(point: E::G2Affine) -> E::G2Prepared {
+        point.into()
+    }
+
+    fn pair(message: E::G1Prepared, key: E::G2Prepared) -> (E::G1Prepared, E::G2Prepared) {
+        (message, key)
+    }
+}
+
+impl<E: PairingEngine> Placement<E> for MessagesInG2 {
+    type Message = E::G2Projective;
+    type Key = E::G1Projective;
+    type PreparedMessage = E::G2Prepared;
+    type PreparedKey = E::G1Prepared;
+
+    fn prepare_message(point: E::G2Affine) -> E::G2Prepared {
+        point.into()
+    }
+
+    fn prepare_key(point: E::G1Affine) -> E::G1Prepared {
+        point.into()
+    }
+
+    fn pair(message: E::G2Prepared, key: E::G1Prepared) -> (E::G1Prepared, E::G2Prepared) {
+        (key, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{convert_pk, convert_sig};
+    use crate::sign::{SigningKey, SpsEqSignature};
+    use crate::verify::PublicKey;
+    use crate::SpsEqSignatureError;
+    use ark_bls12_381::{Bls12_381, Fr, G2Projective as G2};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_messages_in_g2() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381, MessagesInG2>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message: Vec<G2> = (0..3).map(|_| G2::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+        assert!(pk.verify(&message, &signature).is_ok());
+        assert!(pk.verify_strict(&message, &signature).is_ok());
+
+        let different_message: Vec<G2> = (0..3).map(|_| G2::rand(rng)).collect();
+        assert_eq!(
+            pk.verify(&different_message, &signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+
+        let (new_signature, new_message) = signature
+            .clone()
+            .generate_new_repr_checked(&message, &pk, rng)
+            .unwrap();
+        assert!(pk.verify(&new_message, &new_signature).is_ok());
+
+        let batch = [
+            (message.as_slice(), &signature),
+            (new_message.as_slice(), &new_signature),
+            (different_message.as_slice(), &signature),
+        ];
+        assert_eq!(pk.batch_verify(&batch, rng), Err(vec![2]));
+
+        let rho = Fr::rand(rng);
+        let converted_signature = convert_sig(&pk, &message, &signature, rho, rng).unwrap();
+        assert!(convert_pk(&pk, rho)
+            .unwrap()
+            .verify(&message, &converted_signature)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_messages_in_g2_from_to_bytes() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381, MessagesInG2>::new(2, rng);
+        let pk = PublicKey::from(&sk);
+        let message: Vec<G2> = (0..2).map(|_| G2::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+
+        // Keys are compressed G1 points, and Z and Y compressed G2 points
+        let bytes_pk = pk.to_bytes().unwrap();
+        assert_eq!(bytes_pk.len(), 1 + 1 + 4 + 2 * 48);
+        assert_eq!(PublicKey::from_bytes(&bytes_pk).unwrap(), pk);
+
+        let bytes_signature = signature.to_bytes().unwrap();
+        assert_eq!(bytes_signature.len(), 1 + 1 + 2 * 96 + 48);
+        assert_eq!(
+            SpsEqSignature::from_bytes(&bytes_signature).unwrap(),
+            signature
+        );
+    }
+}
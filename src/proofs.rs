@@ -0,0 +1,599 @@
+//! Module describing zero-knowledge proofs of knowledge over SPS-EQ messages.
+//!
+//! Proofs are Schnorr-style $\Sigma$-protocols for linear relations, made non-interactive with
+//! the Fiat-Shamir transform over a [merlin] transcript. A linear relation over a group
+//! $\mathbb{G}$ states knowledge of a witness $w\in\mathbb Z_p^n$ such that
+//! $\sum_{k\in\left[n\right]} w_k A_{j, k} = Y_j$ for every equation $j$, with public
+//! $A_{j, k}, Y_j\in\mathbb{G}$. The prover picks $r\in_R\mathbb Z_p^n$, the challenge $c$ is
+//! derived from the relation and the commitments $T_j = \sum_k r_k A_{j, k}$, and the proof
+//! consists of $c$ and the responses $s = r + c w$. The verifier recomputes
+//! $T_j = \sum_k s_k A_{j, k} - c Y_j$ and checks that they lead to the same challenge.
+//!
+//! Callers may append context, such as the signature a proof accompanies, to the transcript
+//! before proving and verifying, in order to bind the proof to it.
+//!
+//! [merlin]: https://merlin.cool
+
+use alloc::vec::Vec;
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField, UniformRand};
+use ark_serialize::{CanonicalSerialize, Read};
+use merlin::Transcript;
+use rand::{CryptoRng, Rng};
+
+use crate::errors::*;
+use crate::serialization::*;
+
+/// Linear relation between a secret witness and public group elements
+#[derive(Clone, Debug)]
+pub struct LinearRelation<G: ProjectiveCurve> {
+    witness_length: usize,
+    equations: Vec<Equation<G>>,
+}
+
+/// Equation `sum_k w_k A_k = image`, keeping the nonzero `A_k` with the index `k`
+#[derive(Clone, Debug)]
+struct Equation<G: ProjectiveCurve> {
+    terms: Vec<(usize, G)>,
+    image: G,
+}
+
+/// Non-interactive proof of knowledge of a witness of a `LinearRelation`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearProof<G: ProjectiveCurve> {
+    challenge: G::ScalarField,
+    responses: Vec<G::ScalarField>,
+}
+
+impl<G: ProjectiveCurve> LinearRelation<G> {
+    /// Creates a relation without equations over a witness of `witness_length` scalars
+    pub fn new(witness_length: usize) -> Self {
+        LinearRelation {
+            witness_length,
+            equations: Vec::new(),
+        }
+    }
+
+    /// Adds the equation `sum_k w_k A_k = image`, where `terms` holds the pairs `(k, A_k)`.
+    /// Fails if an index exceeds the length of the witness.
+    pub fn add_equation(
+        &mut self,
+        terms: Vec<(usize, G)>,
+        image: G,
+    ) -> Result<(), SpsEqSignatureError> {
+        if terms.iter().any(|(index, _)| *index >= self.witness_length) {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        self.equations.push(Equation { terms, image });
+        Ok(())
+    }
+
+    /// Proves knowledge of `witness`, which must satisfy the relation
+    pub fn prove<R>(
+        &self,
+        transcript: &mut Transcript,
+        witness: &[G::ScalarField],
+        rng: &mut R,
+    ) -> Result<LinearProof<G>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if witness.len() != self.witness_length {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        let nonces: Vec<G::ScalarField> = (0..self.witness_length)
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+        let commitments: Vec<G> = self
+            .equations
+            .iter()
+            .map(|equation| equation.evaluate(&nonces))
+            .collect();
+        let challenge = self.challenge(transcript, &commitments)?;
+
+        let responses = nonces
+            .iter()
+            .zip(witness)
+            .map(|(nonce, secret)| *nonce + challenge * secret)
+            .collect();
+
+        Ok(LinearProof {
+            challenge,
+            responses,
+        })
+    }
+
+    /// Verifies a proof of knowledge of a witness of the relation
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        proof: &LinearProof<G>,
+    ) -> Result<(), SpsEqSignatureError> {
+        if proof.responses.len() != self.witness_length {
+            return Err(SpsEqSignatureError::InvalidProof);
+        }
+
+        let challenge = proof.challenge.into_repr();
+        let commitments: Vec<G> = self
+            .equations
+            .iter()
+            .map(|equation| equation.evaluate(&proof.responses) - equation.image.mul(challenge))
+            .collect();
+
+        if self.challenge(transcript, &commitments)? != proof.challenge {
+            return Err(SpsEqSignatureError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Derives the challenge from the relation and the commitments of the prover
+    fn challenge(
+        &self,
+        transcript: &mut Transcript,
+        commitments: &[G],
+    ) -> Result<G::ScalarField, SpsEqSignatureError> {
+        transcript.append_message(b"dom-sep", b"linear-relation");
+        transcript.append_u64(b"witness-length", self.witness_length as u64);
+        transcript.append_u64(b"equations", self.equations.len() as u64);
+        for equation in &self.equations {
+            transcript.append_u64(b"terms", equation.terms.len() as u64);
+            for (index, base) in &equation.terms {
+                transcript.append_u64(b"index", *index as u64);
+                append_point(transcript, b"base", base)?;
+            }
+            append_point(transcript, b"image", &equation.image)?;
+        }
+        for commitment in commitments {
+            append_point(transcript, b"commitment", commitment)?;
+        }
+
+        Ok(challenge_scalar(transcript, b"challenge"))
+    }
+}
+
+impl<G: ProjectiveCurve> LinearProof<G> {
+    /// Convert a `LinearProof` to an array of bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut writer = Vec::new();
+        write_version(&mut writer);
+        self.write_fields(&mut writer)?;
+        Ok(writer)
+    }
+
+    /// Create a `LinearProof` from an array of bytes produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
+        let mut reader = ByteReader::new(bytes);
+        reader.read_version()?;
+        let proof = Self::read_fields(&mut reader)?;
+        reader.finish()?;
+        Ok(proof)
+    }
+
+    /// Writes the challenge and the responses, for proofs nested in other encodings
+    pub(crate) fn write_fields(&self, writer: &mut Vec<u8>) -> Result<(), SpsEqSignatureError> {
+        write_scalar(writer, &self.challenge)?;
+        write_length(writer, self.responses.len())?;
+        for response in &self.responses {
+            write_scalar(writer, response)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read_fields<R: Read>(
+        reader: &mut ByteReader<R>,
+    ) -> Result<Self, SpsEqSignatureError> {
+        let challenge = reader.read_scalar()?;
+        let length = reader.read_length(scalar_size::<G::ScalarField>())?;
+        // The length prefix is not checked against the size of a stream, so we do not
+        // preallocate from it
+        let mut responses = Vec::new();
+        for _ in 0..length {
+            responses.push(reader.read_scalar()?);
+        }
+        Ok(LinearProof {
+            challenge,
+            responses,
+        })
+    }
+}
+
+impl<G: ProjectiveCurve> Equation<G> {
+    fn evaluate(&self, scalars: &[G::ScalarField]) -> G {
+        self.terms
+            .iter()
+            .map(|(index, base)| base.mul(scalars[*index].into_repr()))
+            .sum()
+    }
+}
+
+/// Proof of knowledge of the opening of a randomised SPS-EQ message `(C, P)`. The message is
+/// a representative `f (sum_i w_i B_i, g)` of the class of a commitment with opening `w` under
+/// the bases `B_i`, where `g` is the generator of the group. The proof shows knowledge of `w`
+/// and of `1/f` without revealing them, so the representative stays unlinkable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpeningProof<G: ProjectiveCurve> {
+    proof: LinearProof<G>,
+}
+
+impl<G: ProjectiveCurve> OpeningProof<G> {
+    /// Proves knowledge of `opening` for the message `(f sum_i w_i B_i, f g)`, where `f` is
+    /// the nonzero randomness of the representative
+    pub fn prove<R>(
+        transcript: &mut Transcript,
+        bases: &[G],
+        message: &[G],
+        opening: &[G::ScalarField],
+        f: G::ScalarField,
+        rng: &mut R,
+    ) -> Result<Self, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        let relation = opening_relation(bases, message)?;
+        let witness = opening_witness(opening, f)?;
+        Ok(OpeningProof {
+            proof: relation.prove(transcript, &witness, rng)?,
+        })
+    }
+
+    /// Verifies the proof of knowledge of the opening of `message` under `bases`
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        bases: &[G],
+        message: &[G],
+    ) -> Result<(), SpsEqSignatureError> {
+        opening_relation(bases, message)?.verify(transcript, &self.proof)
+    }
+
+    /// Convert an `OpeningProof` to an array of bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        self.proof.to_bytes()
+    }
+
+    /// Create an `OpeningProof` from an array of bytes produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
+        Ok(OpeningProof {
+            proof: LinearProof::from_bytes(bytes)?,
+        })
+    }
+}
+
+/// Proof that a randomised SPS-EQ message `f (sum_i w_i B_i, g)` opens to a vector `w` whose
+/// inner product with a public policy vector equals a claimed value. The policy applies to the
+/// first components of `w`, and the proof also shows knowledge of the opening, as
+/// `OpeningProof` does, without revealing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InnerProductProof<G: ProjectiveCurve> {
+    proof: LinearProof<G>,
+}
+
+impl<G: ProjectiveCurve> InnerProductProof<G> {
+    /// Proves knowledge of `opening` for the message `(f sum_i w_i B_i, f g)`, and that its
+    /// inner product with `policy` equals the returned value
+    pub fn prove<R>(
+        transcript: &mut Transcript,
+        bases: &[G],
+        message: &[G],
+        opening: &[G::ScalarField],
+        f: G::ScalarField,
+        policy: &[G::ScalarField],
+        rng: &mut R,
+    ) -> Result<(Self, G::ScalarField), SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if policy.len() > opening.len() {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+        let value = policy
+            .iter()
+            .zip(opening)
+            .map(|(weight, secret)| *weight * secret)
+            .sum();
+
+        let relation = inner_product_relation(bases, message, policy, value)?;
+        let witness = opening_witness(opening, f)?;
+        let proof = InnerProductProof {
+            proof: relation.prove(transcript, &witness, rng)?,
+        };
+        Ok((proof, value))
+    }
+
+    /// Verifies that `message` opens under `bases` to a vector whose inner product with
+    /// `policy` equals `value`
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        bases: &[G],
+        message: &[G],
+        policy: &[G::ScalarField],
+        value: G::ScalarField,
+    ) -> Result<(), SpsEqSignatureError> {
+        inner_product_relation(bases, message, policy, value)?.verify(transcript, &self.proof)
+    }
+
+    /// Convert an `InnerProductProof` to an array of bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        self.proof.to_bytes()
+    }
+
+    /// Create an `InnerProductProof` from an array of bytes produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
+        Ok(InnerProductProof {
+            proof: LinearProof::from_bytes(bytes)?,
+        })
+    }
+}
+
+/// Opening relation with the additional equation `sum_i p_i w_i g = value g`
+fn inner_product_relation<G: ProjectiveCurve>(
+    bases: &[G],
+    message: &[G],
+    policy: &[G::ScalarField],
+    value: G::ScalarField,
+) -> Result<LinearRelation<G>, SpsEqSignatureError> {
+    let mut relation = opening_relation(bases, message)?;
+
+    let generator = G::prime_subgroup_generator();
+    let terms = policy
+        .iter()
+        .enumerate()
+        .map(|(index, weight)| (index + 1, generator.mul(weight.into_repr())))
+        .collect();
+    relation.add_equation(terms, generator.mul(value.into_repr()))?;
+
+    Ok(relation)
+}
+
+/// Relation stating knowledge of `alpha` and `w` with `alpha P = g` and
+/// `alpha C = sum_i w_i B_i`, over the witness `(alpha, w_1, ..., w_n)`
+pub(crate) fn opening_relation<G: ProjectiveCurve>(
+    bases: &[G],
+    message: &[G],
+) -> Result<LinearRelation<G>, SpsEqSignatureError> {
+    if message.len() != 2 || bases.is_empty() {
+        return Err(SpsEqSignatureError::UnmatchedCapacity);
+    }
+
+    let mut relation = LinearRelation::new(bases.len() + 1);
+    relation.add_equation(vec![(0, message[1])], G::prime_subgroup_generator())?;
+
+    let mut terms = vec![(0, message[0])];
+    terms.extend(
+        bases
+            .iter()
+            .enumerate()
+            .map(|(index, base)| (index + 1, -*base)),
+    );
+    relation.add_equation(terms, G::zero())?;
+
+    Ok(relation)
+}
+
+/// Witness `(1/f, w_1, ..., w_n)` of the opening relation
+pub(crate) fn opening_witness<F: Field>(
+    opening: &[F],
+    f: F,
+) -> Result<Vec<F>, SpsEqSignatureError> {
+    let alpha = f.inverse().ok_or(SpsEqSignatureError::InvalidRandomness)?;
+
+    let mut witness = vec![alpha];
+    witness.extend_from_slice(opening);
+    Ok(witness)
+}
+
+fn append_point<G: ProjectiveCurve>(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    point: &G,
+) -> Result<(), SpsEqSignatureError> {
+    let mut bytes = Vec::new();
+    point
+        .into_affine()
+        .serialize(&mut bytes)
+        .map_err(|_| SpsEqSignatureError::IoErrorWrite)?;
+    transcript.append_message(label, &bytes);
+    Ok(())
+}
+
+/// Derives a scalar from 64 bytes of the transcript, so that its bias is negligible
+fn challenge_scalar<F: PrimeField>(transcript: &mut Transcript, label: &'static [u8]) -> F {
+    let mut bytes = [0u8; 64];
+    transcript.challenge_bytes(label, &mut bytes);
+    F::from_le_bytes_mod_order(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::SigningKey;
+    use crate::verify::PublicKey;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use ark_ff::Zero;
+    use rand::thread_rng;
+
+    fn commit(bases: &[G1], opening: &[Fr]) -> G1 {
+        bases
+            .iter()
+            .zip(opening)
+            .map(|(base, value)| base.mul(value.into_repr()))
+            .sum()
+    }
+
+    #[test]
+    fn test_linear_relation() {
+        let rng = &mut thread_rng();
+        let bases: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let witness: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+
+        let mut relation = LinearRelation::new(3);
+        let terms = bases.iter().cloned().enumerate().collect();
+        relation
+            .add_equation(terms, commit(&bases, &witness))
+            .unwrap();
+        relation
+            .add_equation(vec![(1, bases[0])], bases[0].mul(witness[1].into_repr()))
+            .unwrap();
+
+        let proof = relation
+            .prove(&mut Transcript::new(b"test"), &witness, rng)
+            .unwrap();
+        assert!(relation
+            .verify(&mut Transcript::new(b"test"), &proof)
+            .is_ok());
+        assert_eq!(
+            relation.verify(&mut Transcript::new(b"other context"), &proof),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        let wrong_witness = vec![witness[0], witness[2], witness[1]];
+        let proof = relation
+            .prove(&mut Transcript::new(b"test"), &wrong_witness, rng)
+            .unwrap();
+        assert_eq!(
+            relation.verify(&mut Transcript::new(b"test"), &proof),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        assert_eq!(
+            relation.add_equation(vec![(3, bases[0])], bases[0]),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+
+    #[test]
+    fn test_opening_proof_of_new_representation() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(2, rng);
+        let pk = PublicKey::from(&sk);
+
+        let bases: Vec<G1> = (0..4).map(|_| G1::rand(rng)).collect();
+        let opening: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        let message = vec![commit(&bases, &opening), G1::prime_subgroup_generator()];
+        let signature = sk.sign(&message, rng).unwrap();
+
+        let (new_signature, new_message, (f, _)) =
+            signature.generate_new_repr_with_randomness(&message, rng);
+        let proof = OpeningProof::prove(
+            &mut Transcript::new(b"test"),
+            &bases,
+            &new_message,
+            &opening,
+            f,
+            rng,
+        )
+        .unwrap();
+
+        assert!(pk.verify(&new_message, &new_signature).is_ok());
+        assert!(proof
+            .verify(&mut Transcript::new(b"test"), &bases, &new_message)
+            .is_ok());
+
+        // The proof does not hold for another representative, or another opening
+        assert_eq!(
+            proof.verify(&mut Transcript::new(b"test"), &bases, &message),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+        let wrong_opening: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        let proof = OpeningProof::prove(
+            &mut Transcript::new(b"test"),
+            &bases,
+            &new_message,
+            &wrong_opening,
+            f,
+            rng,
+        )
+        .unwrap();
+        assert_eq!(
+            proof.verify(&mut Transcript::new(b"test"), &bases, &new_message),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        assert_eq!(
+            OpeningProof::prove(
+                &mut Transcript::new(b"test"),
+                &bases,
+                &new_message,
+                &opening,
+                Fr::zero(),
+                rng,
+            ),
+            Err(SpsEqSignatureError::InvalidRandomness)
+        );
+    }
+
+    #[test]
+    fn test_inner_product_proof() {
+        let rng = &mut thread_rng();
+        let bases: Vec<G1> = (0..5).map(|_| G1::rand(rng)).collect();
+        let opening: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let policy: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+
+        let f = Fr::rand(rng);
+        let message = vec![
+            commit(&bases, &opening).mul(f.into_repr()),
+            G1::prime_subgroup_generator().mul(f.into_repr()),
+        ];
+
+        let (proof, value) = InnerProductProof::<G1>::prove(
+            &mut Transcript::new(b"test"),
+            &bases,
+            &message,
+            &opening,
+            f,
+            &policy,
+            rng,
+        )
+        .unwrap();
+        let expected = policy[0] * opening[0] + policy[1] * opening[1] + policy[2] * opening[2];
+        assert_eq!(value, expected);
+
+        assert!(proof
+            .verify(
+                &mut Transcript::new(b"test"),
+                &bases,
+                &message,
+                &policy,
+                value
+            )
+            .is_ok());
+        assert_eq!(
+            proof.verify(
+                &mut Transcript::new(b"test"),
+                &bases,
+                &message,
+                &policy,
+                value + Fr::from(1u64)
+            ),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+        let other_policy: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        assert_eq!(
+            proof.verify(
+                &mut Transcript::new(b"test"),
+                &bases,
+                &message,
+                &other_policy,
+                value
+            ),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        let long_policy: Vec<Fr> = (0..6).map(|_| Fr::rand(rng)).collect();
+        assert_eq!(
+            InnerProductProof::<G1>::prove(
+                &mut Transcript::new(b"test"),
+                &bases,
+                &message,
+                &opening,
+                f,
+                &long_policy,
+                rng,
+            ),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Serde support for the public types of the crate, enabled with the `serde` feature.
+//!
+//! The types are serialized through their byte encodings, see for instance
+//! [`SpsEqSignature::to_bytes`]: as a lowercase hex string in human-readable formats such as
+//! JSON, and as raw bytes in binary formats such as CBOR. Deserialization runs the same checks
+//! as `from_bytes`, so points off the curve or outside the prime-order subgroup are rejected.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+use zeroize::Zeroize;
+
+use crate::credentials::CredentialShow;
+use crate::placement::Placement;
+use crate::proofs::{InnerProductProof, LinearProof, OpeningProof};
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::verify::PublicKey;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Maximum number of bytes preallocated from the size hint of a sequence
+const MAX_PREALLOCATION: usize = 4096;
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        encoded.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        encoded.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    encoded
+}
+
+fn decode_hex(encoded: &str) -> Option<Vec<u8>> {
+    fn digit(character: u8) -> Option<u8> {
+        match character {
+            b'0'..=b'9' => Some(character - b'0'),
+            b'a'..=b'f' => Some(character - b'a' + 10),
+            b'A'..=b'F' => Some(character - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let pairs = encoded.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| Some((digit(pair[0])? << 4) | digit(pair[1])?))
+        .collect()
+}
+
+fn serialize_bytes<S: Serializer>(mut bytes: Vec<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+    let result = if serializer.is_human_readable() {
+        let mut encoded = encode_hex(&bytes);
+        let result = serializer.serialize_str(&encoded);
+        encoded.zeroize();
+        result
+    } else {
+        serializer.serialize_bytes(&bytes)
+    };
+    // The encoding of a signing key is secret
+    bytes.zeroize();
+    result
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(HexVisitor)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+struct HexVisitor;
+
+impl<'de> Visitor<'de> for HexVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a hex encoded string")
+    }
+
+    fn visit_str<Er: de::Error>(self, value: &str) -> Result<Self::Value, Er> {
+        decode_hex(value).ok_or_else(|| Er::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<Er: de::Error>(self, value: &[u8]) -> Result<Self::Value, Er> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<Er: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, Er> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // The size hint comes from the input, so it only bounds a small preallocation
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATION));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` through the `to_bytes` and `from_bytes` methods of
+/// a type
+macro_rules! impl_serde_via_bytes {
+    ($name:ident, [$($generics:tt)*], [$($params:tt)*]) => {
+        impl<$($generics)*> Serialize for $name<$($params)*> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let bytes = self.to_bytes().map_err(<S::Error as ser::Error>::custom)?;
+                serialize_bytes(bytes, serializer)
+            }
+        }
+
+        impl<'de, $($generics)*> Deserialize<'de> for $name<$($params)*> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let mut bytes = deserialize_bytes(deserializer)?;
+                let value = Self::from_bytes(&bytes);
+                bytes.zeroize();
+                value.map_err(<D::Error as de::Error>::custom)
+            }
+        }
+    };
+}
+
+impl_serde_via_bytes!(SpsEqSignature, [E: PairingEngine, P: Placement<E>], [E, P]);
+impl_serde_via_bytes!(PublicKey, [E: PairingEngine, P: Placement<E>], [E, P]);
+impl_serde_via_bytes!(SigningKey, [E: PairingEngine, P: Placement<E>], [E, P]);
+impl_serde_via_bytes!(LinearProof, [G: ProjectiveCurve], [G]);
+impl_serde_via_bytes!(OpeningProof, [G: ProjectiveCurve], [G]);
+impl_serde_via_bytes!(InnerProductProof, [G: ProjectiveCurve], [G]);
+impl_serde_via_bytes!(CredentialShow, [E: PairingEngine], [E]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placement::MessagesInG2;
+    use crate::set_commitment::TrustedSetup;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use ark_ff::{PrimeField, UniformRand};
+    use merlin::Transcript;
+    use rand::thread_rng;
+    use serde::de::DeserializeOwned;
+
+    fn assert_round_trips<T>(value: &T)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + fmt::Debug,
+    {
+        let json = serde_json::to_value(value).unwrap();
+        let encoded = json
+            .as_str()
+            .expect("human-readable formats use a hex string");
+        assert!(encoded.bytes().all(|c| HEX_DIGITS.contains(&c)));
+        assert_eq!(&serde_json::from_value::<T>(json).unwrap(), value);
+
+        let cbor = serde_cbor::to_vec(value).unwrap();
+        match serde_cbor::from_slice(&cbor).unwrap() {
+            serde_cbor::Value::Bytes(_) => {}
+            _ => panic!("binary formats use raw bytes"),
+        }
+        assert_eq!(&serde_cbor::from_slice::<T>(&cbor).unwrap(), value);
+    }
+
+    #[test]
+    fn test_serde_keys_and_signatures() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<Bls12_381>::new(3, rng);
+        let public_key = PublicKey::from(&signing_key);
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let signature = signing_key.sign(&message, rng).unwrap();
+
+        assert_round_trips(&signing_key);
+        assert_round_trips(&public_key);
+        assert_round_trips(&signature);
+
+        let signing_key = SigningKey::<Bls12_381, MessagesInG2>::new(2, rng);
+        assert_round_trips(&signing_key);
+        assert_round_trips(&PublicKey::from(&signing_key));
+    }
+
+    #[test]
+    fn test_serde_proofs_and_credentials() {
+        let rng = &mut thread_rng();
+        let bases: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let opening: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let f = Fr::rand(rng);
+        let commitment: G1 = bases
+            .iter()
+            .zip(opening.iter())
+            .map(|(base, value)| base.mul(value.into_repr()))
+            .sum();
+        let message = vec![
+            commitment.mul(f.into_repr()),
+            G1::prime_subgroup_generator().mul(f.into_repr()),
+        ];
+        let proof = OpeningProof::prove(
+            &mut Transcript::new(b"test"),
+            &bases,
+            &message,
+            &opening,
+            f,
+            rng,
+        )
+        .unwrap();
+        assert_round_trips(&proof);
+
+        let params = TrustedSetup::<Bls12_381>::new(4, rng);
+        let issuer = crate::credentials::Issuer::new(rng);
+        let attributes: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let (request, pending) =
+            crate::credentials::CredentialRequest::new(&params, &attributes, rng).unwrap();
+        let signature = issuer.issue(&params, &request, rng).unwrap();
+        let credential = pending.finish(&issuer.public_key, signature).unwrap();
+        let show = credential
+            .show(
+                &params,
+                &attributes[..1],
+                &mut Transcript::new(b"test"),
+                rng,
+            )
+            .unwrap();
+        assert_round_trips(&show);
+    }
+
+    #[test]
+    fn test_serde_rejects_invalid_encodings() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<Bls12_381>::new(2, rng);
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        let signature = signing_key.sign(&message, rng).unwrap();
+
+        let encoded = serde_json::to_string(&signature).unwrap();
+        let odd_length = format!("\"{}\"", &encoded[1..encoded.len() - 2]);
+        assert!(serde_json::from_str::<SpsEqSignature<Bls12_381>>(&odd_length).is_err());
+        let not_hex = format!("\"zz{}", &encoded[3..]);
+        assert!(serde_json::from_str::<SpsEqSignature<Bls12_381>>(&not_hex).is_err());
+
+        let mut bytes = signature.to_bytes().unwrap();
+        bytes.push(0);
+        let cbor = serde_cbor::to_vec(&serde_cbor::Value::Bytes(bytes)).unwrap();
+        assert!(serde_cbor::from_slice::<SpsEqSignature<Bls12_381>>(&cbor).is_err());
+    }
+
+    /// Sequence of bytes announcing far more elements than it holds
+    struct LyingSeq(Vec<u8>);
+
+    impl<'de> SeqAccess<'de> for LyingSeq {
+        type Error = de::value::Error;
+
+        fn next_element_seed<T: de::DeserializeSeed<'de>>(
+            &mut self,
+            seed: T,
+        ) -> Result<Option<T::Value>, Self::Error> {
+            match self.0.pop() {
+                Some(byte) => seed
+                    .deserialize(de::value::U8Deserializer::<Self::Error>::new(byte))
+                    .map(Some),
+                None => Ok(None),
+            }
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(usize::MAX)
+        }
+    }
+
+    #[test]
+    fn test_serde_ignores_oversized_hints() {
+        let bytes = BytesVisitor.visit_seq(LyingSeq(vec![3, 2, 1])).unwrap();
+        assert_eq!(bytes, vec![1, 2, 3]);
+    }
+}
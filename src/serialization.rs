@@ -0,0 +1,229 @@
+//! Wire format shared by the `to_bytes` and `from_bytes` helpers of the crate types, and by
+//! their `CanonicalSerialize` and `CanonicalDeserialize` implementations.
+//!
+//! Every encoding starts with a version byte. Types holding group elements follow it with a
+//! flag selecting compressed or uncompressed points, and vectors carry their length as a
+//! big-endian `u32`. Point and scalar sizes are taken from the curves of the `PairingEngine`,
+//! and `from_bytes` rejects trailing bytes. The canonical deserialization reads exactly the
+//! bytes of the object instead, so that it can be nested in larger structures.
+//!
+//! Decoding never panics: encodings come from untrusted sources, so every malformed input maps
+//! to a specific [`SpsEqSignatureError`]. Points must be canonically encoded, lie in the
+//! prime-order subgroup and differ from the identity.
+
+use alloc::vec::Vec;
+use ark_ec::AffineCurve;
+use ark_ff::PrimeField;
+use ark_serialize::Read;
+
+use crate::errors::*;
+use crate::utils::is_in_prime_order_subgroup;
+
+/// Version of the wire format
+pub(crate) const VERSION: u8 = 1;
+
+const COMPRESSED: u8 = 0;
+const UNCOMPRESSED: u8 = 1;
+
+/// Size in bytes of the encoding of a point of `G`
+pub(crate) fn point_size<G: AffineCurve>(compressed: bool) -> usize {
+    if compressed {
+        G::zero().serialized_size()
+    } else {
+        G::zero().uncompressed_size()
+    }
+}
+
+/// Size in bytes of the encoding of a scalar of `F`
+pub(crate) fn scalar_size<F: PrimeField>() -> usize {
+    F::zero().serialized_size()
+}
+
+pub(crate) fn write_version(writer: &mut Vec<u8>) {
+    writer.push(VERSION);
+}
+
+pub(crate) fn write_point_encoding(writer: &mut Vec<u8>, compressed: bool) {
+    writer.push(if compressed { COMPRESSED } else { UNCOMPRESSED });
+}
+
+pub(crate) fn write_length(writer: &mut Vec<u8>, length: usize) -> Result<(), SpsEqSignatureError> {
+    if length > u32::MAX as usize {
+        return Err(SpsEqSignatureError::IoErrorWrite);
+    }
+    writer.extend_from_slice(&(length as u32).to_be_bytes());
+    Ok(())
+}
+
+pub(crate) fn write_point<G: AffineCurve>(
+    writer: &mut Vec<u8>,
+    point: &G,
+    compressed: bool,
+) -> Result<(), SpsEqSignatureError> {
+    let result = if compressed {
+        point.serialize(&mut *writer)
+    } else {
+        point.serialize_uncompressed(&mut *writer)
+    };
+    result.map_err(|_| SpsEqSignatureError::IoErrorWrite)
+}
+
+pub(crate) fn write_scalar<F: PrimeField>(
+    writer: &mut Vec<u8>,
+    scalar: &F,
+) -> Result<(), SpsEqSignatureError> {
+    scalar
+        .serialize(&mut *writer)
+        .map_err(|_| SpsEqSignatureError::IoErrorWrite)
+}
+
+/// Cursor over an encoded object, consuming the fields in the order they were written
+pub(crate) struct ByteReader<R: Read> {
+    reader: R,
+    /// Number of bytes left in the input, when it is known
+    remaining: Option<usize>,
+    /// Whether points and keys are checked, see [`ByteReader::unchecked`]
+    validate: bool,
+}
+
+impl<'a> ByteReader<&'a [u8]> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        ByteReader {
+            reader: bytes,
+            remaining: Some(bytes.len()),
+            validate: true,
+        }
+    }
+
+    /// Ensures the whole input was consumed
+    pub(crate) fn finish(self) -> Result<(), SpsEqSignatureError> {
+        if self.reader.is_empty() {
+            Ok(())
+        } else {
+            Err(SpsEqSignatureError::TrailingBytes)
+        }
+    }
+}
+
+impl<R: Read> ByteReader<R> {
+    /// Cursor over a stream, reading exactly the bytes of the encoded object, as needed by the
+    /// `CanonicalDeserialize` implementations
+    pub(crate) fn from_reader(reader: R) -> Self {
+        ByteReader {
+            reader,
+            remaining: None,
+            validate: true,
+        }
+    }
+
+    /// Skips the checks on the decoded values, which must then come from a trusted source.
+    /// Points are neither checked to be canonical, in the subgroup or different from the
+    /// identity, nor to be on the curve when uncompressed, and secret keys are taken as is.
+    pub(crate) fn unchecked(mut self) -> Self {
+        self.validate = false;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> bool {
+        self.validate
+    }
+
+    fn take(&mut self, size: usize) -> Result<Vec<u8>, SpsEqSignatureError> {
+        if let Some(remaining) = self.remaining {
+            if remaining < size {
+                return Err(SpsEqSignatureError::TruncatedInput);
+            }
+            self.remaining = Some(remaining - size);
+        }
+        let mut bytes = vec![0u8; size];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|_| SpsEqSignatureError::TruncatedInput)?;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_version(&mut self) -> Result<(), SpsEqSignatureError> {
+        match self.take(1)?[0] {
+            VERSION => Ok(()),
+            _ => Err(SpsEqSignatureError::UnsupportedVersion),
+        }
+    }
+
+    /// Reads the point encoding flag, returning whether points are compressed
+    pub(crate) fn read_point_encoding(&mut self) -> Result<bool, SpsEqSignatureError> {
+        match self.take(1)?[0] {
+            COMPRESSED => Ok(true),
+            UNCOMPRESSED => Ok(false),
+            _ => Err(SpsEqSignatureError::InvalidPointEncoding),
+        }
+    }
+
+    /// Reads the length prefix of a vector whose items take `item_size` bytes each. When the
+    /// size of the input is known, the prefix is rejected as truncated if the rest of the input
+    /// cannot hold that many items. Otherwise, callers must not preallocate from the prefix,
+    /// which may be forged.
+    pub(crate) fn read_length(&mut self, item_size: usize) -> Result<usize, SpsEqSignatureError> {
+        let mut length = [0u8; 4];
+        length.copy_from_slice(&self.take(4)?);
+        let length = u32::from_be_bytes(length) as usize;
+
+        match (length.checked_mul(item_size), self.remaining) {
+            (Some(size), Some(remaining)) if size <= remaining => Ok(length),
+            (Some(_), None) => Ok(length),
+            _ => Err(SpsEqSignatureError::TruncatedInput),
+        }
+    }
+
+    pub(crate) fn read_point<G: AffineCurve>(
+        &mut self,
+        compressed: bool,
+    ) -> Result<G, SpsEqSignatureError> {
+        let bytes = self.take(point_size::<G>(compressed))?;
+
+        let point = if compressed {
+            // `from_random_bytes` decompresses the point without the subgroup check, which is
+            // performed below so that both failures can be told apart.
+            G::from_random_bytes(&bytes).ok_or(SpsEqSignatureError::InvalidPointEncoding)?
+        } else if !self.validate {
+            return G::deserialize_unchecked(&bytes[..])
+                .map_err(|_| SpsEqSignatureError::InvalidPointEncoding);
+        } else {
+            let point = G::deserialize_unchecked(&bytes[..])
+                .map_err(|_| SpsEqSignatureError::InvalidPointEncoding)?;
+            // The unchecked decoding does not ensure that the coordinates satisfy the curve
+            // equation, so we recover the point from its x-coordinate and compare.
+            let mut compressed_point = Vec::new();
+            write_point(&mut compressed_point, &point, true)
+                .map_err(|_| SpsEqSignatureError::InvalidPointEncoding)?;
+            if G::from_random_bytes(&compressed_point) != Some(point) {
+                return Err(SpsEqSignatureError::InvalidPointEncoding);
+            }
+            point
+        };
+        if !self.validate {
+            return Ok(point);
+        }
+
+        // Reject malleable encodings, e.g. with padding bits set
+        let mut encoding = Vec::new();
+        write_point(&mut encoding, &point, compressed)
+            .map_err(|_| SpsEqSignatureError::InvalidPointEncoding)?;
+        if encoding != bytes {
+            return Err(SpsEqSignatureError::InvalidPointEncoding);
+        }
+
+        if point.is_zero() {
+            return Err(SpsEqSignatureError::IdentityPoint);
+        }
+        if !is_in_prime_order_subgroup(&point.into_projective()) {
+            return Err(SpsEqSignatureError::PointNotInSubgroup);
+        }
+
+        Ok(point)
+    }
+
+    pub(crate) fn read_scalar<F: PrimeField>(&mut self) -> Result<F, SpsEqSignatureError> {
+        let bytes = self.take(scalar_size::<F>())?;
+        F::deserialize(&bytes[..]).map_err(|_| SpsEqSignatureError::InvalidScalarEncoding)
+    }
+}
@@ -0,0 +1,349 @@
+//! Module describing the set-commitment scheme of Fuchsbauer, Hanser and Slamanig, with
+//! subset openings.
+//!
+//! A set $S\subset\mathbb Z_p$ is encoded as the polynomial $f_S(X) = \prod_{s\in S}(X - s)$,
+//! whose roots are its elements. The public parameters are the powers $(a^i g_1)_i$ and
+//! $(a^i g_2)_i$ of a trapdoor $a$, produced by a trusted setup, see [`TrustedSetup`]. The
+//! commitment to $S$ with the opening $\rho\in\mathbb Z_p^*$ is the $\mathbb{G}_1$ element
+//! $C = \rho f_S(a) g_1$, computed from the coefficients of $f_S$ without knowing $a$.
+//!
+//! A subset $D\subseteq S$ is opened with the witness $W = \rho f_{S\setminus D}(a) g_1$, and
+//! the verifier checks that $e(W, f_D(a) g_2) = e(C, g_2)$. The witness reveals nothing about
+//! the undisclosed elements of $S$.
+//!
+//! Commitments are elements of $\mathbb{G}_1$, so they can be signed as message components with
+//! [`SigningKey::sign`](crate::sign::SigningKey::sign). Multiplying a commitment and its opening
+//! by $\mu$ yields a commitment to the same set, which is how the representation of a signed
+//! commitment changes.
+
+use alloc::vec::Vec;
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use rand::{CryptoRng, Rng};
+
+use crate::errors::*;
+use crate::utils::{is_in_prime_order_subgroup, random_nonzero_scalar};
+
+/// Set commitment in `G1` together with its opening
+pub type CommitmentWithOpening<E> = (<E as PairingEngine>::G1Projective, <E as PairingEngine>::Fr);
+
+/// Powers-of-tau public parameters of the set commitments, for sets of up to `max_size`
+/// elements
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustedSetup<E: PairingEngine> {
+    g1_powers: Vec<E::G1Projective>,
+    g2_powers: Vec<E::G2Projective>,
+}
+
+impl<E: PairingEngine> TrustedSetup<E> {
+    /// Generates the parameters for sets of up to `max_size` elements. The trapdoor is dropped
+    /// once the powers are computed, so the party running this function must be trusted to
+    /// forget it.
+    pub fn new<R>(max_size: usize, rng: &mut R) -> Self
+    where
+        R: Rng + CryptoRng,
+    {
+        let trapdoor = E::Fr::rand(rng);
+        let mut g1_powers = Vec::with_capacity(max_size + 1);
+        let mut g2_powers = Vec::with_capacity(max_size + 1);
+        let mut power = E::Fr::one();
+        for _ in 0..=max_size {
+            g1_powers.push(E::G1Projective::prime_subgroup_generator().mul(power.into_repr()));
+            g2_powers.push(E::G2Projective::prime_subgroup_generator().mul(power.into_repr()));
+            power *= trapdoor;
+        }
+
+        TrustedSetup {
+            g1_powers,
+            g2_powers,
+        }
+    }
+
+    /// Builds the parameters from the output of a powers-of-tau ceremony, checking that the
+    /// points are consecutive powers of the same trapdoor over the generators
+    pub fn from_powers(
+        g1_powers: Vec<E::G1Projective>,
+        g2_powers: Vec<E::G2Projective>,
+    ) -> Result<Self, SpsEqSignatureError> {
+        if g1_powers.len() < 2 || g1_powers.len() != g2_powers.len() {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+        if g1_powers[0] != E::G1Projective::prime_subgroup_generator()
+            || g2_powers[0] != E::G2Projective::prime_subgroup_generator()
+        {
+            return Err(SpsEqSignatureError::InvalidParameters);
+        }
+        if !g1_powers.iter().all(is_in_prime_order_subgroup)
+            || !g2_powers.iter().all(is_in_prime_order_subgroup)
+        {
+            return Err(SpsEqSignatureError::PointNotInSubgroup);
+        }
+
+        // e(a^{i+1} g1, g2) = e(a^i g1, a g2) and e(a^i g1, g2) = e(g1, a^i g2)
+        let g1 = E::G1Affine::prime_subgroup_generator();
+        let g2 = E::G2Affine::prime_subgroup_generator();
+        let trapdoor_g2 = g2_powers[1].into_affine();
+        for i in 0..g1_powers.len() {
+            let g1_power = g1_powers[i].into_affine();
+            if i + 1 < g1_powers.len() {
+                let next_power = g1_powers[i + 1].into_affine();
+                if !equal_pairings::<E>(next_power, g2, g1_power, trapdoor_g2) {
+                    return Err(SpsEqSignatureError::InvalidParameters);
+                }
+            }
+            if !equal_pairings::<E>(g1_power, g2, g1, g2_powers[i].into_affine()) {
+                return Err(SpsEqSignatureError::InvalidParameters);
+            }
+        }
+
+        Ok(TrustedSetup {
+            g1_powers,
+            g2_powers,
+        })
+    }
+
+    /// Maximum number of elements of a committed set
+    pub fn max_size(&self) -> usize {
+        self.g1_powers.len() - 1
+    }
+
+    /// Commits to `set` with a fresh opening
+    pub fn commit<R>(
+        &self,
+        set: &[E::Fr],
+        rng: &mut R,
+    ) -> Result<CommitmentWithOpening<E>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        let opening = random_nonzero_scalar(rng);
+        Ok((self.commit_with(set, opening)?, opening))
+    }
+
+    /// Commits to `set` with the given nonzero `opening`
+    pub fn commit_with(
+        &self,
+        set: &[E::Fr],
+        opening: E::Fr,
+    ) -> Result<E::G1Projective, SpsEqSignatureError> {
+        if opening.is_zero() {
+            return Err(SpsEqSignatureError::InvalidRandomness);
+        }
+
+        let commitment =
+            evaluate_in_exponent(&self.g1_powers, &set_polynomial(set))?.mul(opening.into_repr());
+        // The commitment is the identity only if the trapdoor belongs to the set
+        if commitment.is_zero() {
+            return Err(SpsEqSignatureError::IdentityPoint);
+        }
+        Ok(commitment)
+    }
+
+    /// Checks that `commitment` opens to `set` with `opening`
+    pub fn verify_opening(
+        &self,
+        commitment: &E::G1Projective,
+        set: &[E::Fr],
+        opening: E::Fr,
+    ) -> Result<(), SpsEqSignatureError> {
+        if *commitment != self.commit_with(set, opening)? {
+            return Err(SpsEqSignatureError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Computes the witness opening the elements `subset` of the set committed with `opening`
+    pub fn open_subset(
+        &self,
+        set: &[E::Fr],
+        opening: E::Fr,
+        subset: &[E::Fr],
+    ) -> Result<E::G1Projective, SpsEqSignatureError> {
+        let difference = set_difference(set, subset)?;
+        Ok(
+            evaluate_in_exponent(&self.g1_powers, &set_polynomial(&difference))?
+                .mul(opening.into_repr()),
+        )
+    }
+
+    /// Checks that `witness` opens the elements `subset` of the set committed in `commitment`
+    pub fn verify_subset(
+        &self,
+        commitment: &E::G1Projective,
+        subset: &[E::Fr],
+        witness: &E::G1Projective,
+    ) -> Result<(), SpsEqSignatureError> {
+        if commitment.is_zero() {
+            return Err(SpsEqSignatureError::IdentityPoint);
+        }
+        if !is_in_prime_order_subgroup(witness) {
+            return Err(SpsEqSignatureError::PointNotInSubgroup);
+        }
+
+        let subset_point = evaluate_in_exponent(&self.g2_powers, &set_polynomial(subset))?;
+        if !equal_pairings::<E>(
+            witness.into_affine(),
+            subset_point.into_affine(),
+            commitment.into_affine(),
+            E::G2Affine::prime_subgroup_generator(),
+        ) {
+            return Err(SpsEqSignatureError::InvalidProof);
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `e(a, b) = e(c, d)` with a single final exponentiation
+fn equal_pairings<E: PairingEngine>(
+    a: E::G1Affine,
+    b: E::G2Affine,
+    c: E::G1Affine,
+    d: E::G2Affine,
+) -> bool {
+    let pairs = [(a.into(), b.into()), ((-c).into(), d.into())];
+    E::product_of_pairings(&pairs).is_one()
+}
+
+/// Polynomial `prod_{s in S} (X - s)` whose roots are the elements of the set
+fn set_polynomial<F: PrimeField>(set: &[F]) -> DensePolynomial<F> {
+    set.iter().fold(
+        DensePolynomial::from_coefficients_vec(vec![F::one()]),
+        |polynomial, element| {
+            polynomial.naive_mul(&DensePolynomial::from_coefficients_vec(vec![
+                -*element,
+                F::one(),
+            ]))
+        },
+    )
+}
+
+/// Evaluates `polynomial` at the trapdoor, in the exponent of the group of `powers`
+fn evaluate_in_exponent<G: ProjectiveCurve>(
+    powers: &[G],
+    polynomial: &DensePolynomial<G::ScalarField>,
+) -> Result<G, SpsEqSignatureError> {
+    let coefficients = polynomial.coeffs();
+    if coefficients.len() > powers.len() {
+        return Err(SpsEqSignatureError::UnmatchedCapacity);
+    }
+
+    let bases = G::batch_normalization_into_affine(&powers[..coefficients.len()]);
+    let scalars: Vec<_> = coefficients
+        .iter()
+        .map(|coefficient| coefficient.into_repr())
+        .collect();
+    Ok(VariableBaseMSM::multi_scalar_mul(&bases, &scalars))
+}
+
+/// Removes the elements of `subset` from `set`, failing if one of them is missing
+fn set_difference<F: PrimeField>(set: &[F], subset: &[F]) -> Result<Vec<F>, SpsEqSignatureError> {
+    let mut difference = set.to_vec();
+    for element in subset {
+        let position = difference
+            .iter()
+            .position(|candidate| candidate == element)
+            .ok_or(SpsEqSignatureError::AttributeNotInSet)?;
+        difference.swap_remove(position);
+    }
+    Ok(difference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::SigningKey;
+    use crate::verify::PublicKey;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1, G2Projective as G2};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_commit_and_open_subsets() {
+        let rng = &mut thread_rng();
+        let setup = TrustedSetup::<Bls12_381>::new(4, rng);
+        let set: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        let (commitment, opening) = setup.commit(&set, rng).unwrap();
+        assert!(setup.verify_opening(&commitment, &set, opening).is_ok());
+        assert_eq!(
+            setup.verify_opening(&commitment, &set[1..], opening),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        for subset in [&set[..0], &set[2..], &[set[3], set[0]], &set[..]].iter() {
+            let witness = setup.open_subset(&set, opening, subset).unwrap();
+            assert!(setup.verify_subset(&commitment, subset, &witness).is_ok());
+        }
+
+        let other_element = Fr::rand(rng);
+        assert_eq!(
+            setup.open_subset(&set, opening, &[other_element]),
+            Err(SpsEqSignatureError::AttributeNotInSet)
+        );
+        let witness = setup.open_subset(&set, opening, &set[..1]).unwrap();
+        assert_eq!(
+            setup.verify_subset(&commitment, &[other_element], &witness),
+            Err(SpsEqSignatureError::InvalidProof)
+        );
+
+        let too_large_set: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        assert_eq!(
+            setup.commit(&too_large_set, rng),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+
+    #[test]
+    fn test_sign_commitments() {
+        let rng = &mut thread_rng();
+        let setup = TrustedSetup::<Bls12_381>::new(3, rng);
+        let set: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let (commitment, opening) = setup.commit(&set, rng).unwrap();
+
+        let sk = SigningKey::<Bls12_381>::new(2, rng);
+        let pk = PublicKey::from(&sk);
+        let message = vec![commitment, G1::prime_subgroup_generator()];
+        let signature = sk.sign(&message, rng).unwrap();
+
+        // A new representative carries a commitment to the same set, with the opening mu rho
+        let mu = Fr::rand(rng);
+        let (new_signature, new_message) = signature
+            .change_repr_with(&message, mu, Fr::rand(rng))
+            .unwrap();
+        assert!(pk.verify(&new_message, &new_signature).is_ok());
+        assert!(setup
+            .verify_opening(&new_message[0], &set, mu * opening)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_setup_from_powers() {
+        let rng = &mut thread_rng();
+        let setup = TrustedSetup::<Bls12_381>::new(3, rng);
+        assert_eq!(
+            TrustedSetup::from_powers(setup.g1_powers.clone(), setup.g2_powers.clone()),
+            Ok(setup.clone())
+        );
+
+        let mut g1_powers = setup.g1_powers.clone();
+        g1_powers[2] = G1::rand(rng);
+        assert_eq!(
+            TrustedSetup::<Bls12_381>::from_powers(g1_powers, setup.g2_powers.clone()),
+            Err(SpsEqSignatureError::InvalidParameters)
+        );
+
+        let mut g2_powers = setup.g2_powers.clone();
+        g2_powers[3] = G2::rand(rng);
+        assert_eq!(
+            TrustedSetup::<Bls12_381>::from_powers(setup.g1_powers.clone(), g2_powers),
+            Err(SpsEqSignatureError::InvalidParameters)
+        );
+
+        assert_eq!(
+            TrustedSetup::<Bls12_381>::from_powers(
+                setup.g1_powers.clone(),
+                setup.g2_powers[..2].to_vec()
+            ),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+}
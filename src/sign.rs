@@ -1,45 +1,57 @@
 //! Module describing the signing procedures and structs
 
-use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
 
-use ark_ff::{Field, UniformRand, Zero};
+use alloc::vec::Vec;
+use ark_ff::{Field, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use core::marker::PhantomData;
 use zeroize::Zeroize;
 
 use crate::errors::*;
+use crate::placement::*;
+use crate::serialization::*;
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
 use rand::{CryptoRng, Rng};
 
-/// SPS-EQ signature
-pub struct SpsEqSignature<E: PairingEngine> {
+/// Randomness `(f, psi)` of a change of representation: the message is multiplied by `f`, and
+/// `psi` re-randomises the signature
+pub type ReprRandomness<E> = (<E as PairingEngine>::Fr, <E as PairingEngine>::Fr);
+
+/// Signature together with the representative of the message class it signs
+pub type Representation<E, P = MessagesInG1> =
+    (SpsEqSignature<E, P>, Vec<<P as Placement<E>>::Message>);
+
+/// SPS-EQ signature, with points in the groups given by the placement `P`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpsEqSignature<E: PairingEngine, P: Placement<E> = MessagesInG1> {
     /// Z point
-    pub Z: E::G1Projective,
+    pub Z: P::Message,
     /// Y point
-    pub Y: E::G1Projective,
+    pub Y: P::Message,
     /// Yp point
-    pub Yp: E::G2Projective,
+    pub Yp: P::Key,
 }
 
-impl<E: PairingEngine> SpsEqSignature<E> {
+impl<E: PairingEngine, P: Placement<E>> SpsEqSignature<E, P> {
     /// Mutably changes the representation of the signature and a message. The
     /// function does not make assumptions with regards to the relation between
     /// the message and the signature (ie. signature may not correspond to the
     /// message)
-    pub fn change_repr<R>(
-        &mut self,
-        message: &[E::G1Projective],
-        rng: &mut R,
-    ) -> Vec<E::G1Projective>
+    pub fn change_repr<R>(&mut self, message: &[P::Message], rng: &mut R) -> Vec<P::Message>
     where
         R: Rng + CryptoRng,
     {
-        let rnd_f = E::Fr::rand(rng);
-        let rnd_u = E::Fr::rand(rng);
+        let rnd_f = random_nonzero_scalar(rng);
+        let rnd_u = random_nonzero_scalar(rng);
 
-        let rnd_signature = SpsEqSignature::<E>::rnd_signature(&self, rnd_u, rnd_f);
+        let rnd_signature = SpsEqSignature::<E, P>::rnd_signature(self, rnd_u, rnd_f);
         self.Z = rnd_signature.Z;
         self.Y = rnd_signature.Y;
         self.Yp = rnd_signature.Yp;
 
-        SpsEqSignature::<E>::rnd_message(message, rnd_f)
+        SpsEqSignature::<E, P>::rnd_message(message, rnd_f)
     }
 
     /// Generates a new representation of the signature and message, and returns
@@ -48,37 +60,174 @@ impl<E: PairingEngine> SpsEqSignature<E> {
     /// and the signature (ie. signature may not correspond to the message)
     pub fn generate_new_repr<R>(
         self,
-        message: &[E::G1Projective],
+        message: &[P::Message],
         rng: &mut R,
-    ) -> (SpsEqSignature<E>, Vec<E::G1Projective>)
+    ) -> (SpsEqSignature<E, P>, Vec<P::Message>)
     where
         R: Rng + CryptoRng,
     {
-        let rnd_f = E::Fr::rand(rng);
-        let rnd_u = E::Fr::rand(rng);
+        let (rnd_signature, rnd_message, _) = self.generate_new_repr_with_randomness(message, rng);
+        (rnd_signature, rnd_message)
+    }
 
-        let rnd_signature = SpsEqSignature::<E>::rnd_signature(&self, rnd_u, rnd_f);
-        let rnd_message = SpsEqSignature::<E>::rnd_message(message, rnd_f);
+    /// Same as `generate_new_repr`, but also returns the randomness `(f, psi)` used, where the
+    /// message is multiplied by `f` and `psi` re-randomises the signature. Protocols built on top
+    /// of SPS-EQ need `f` to update the openings of the new representation.
+    pub fn generate_new_repr_with_randomness<R>(
+        &self,
+        message: &[P::Message],
+        rng: &mut R,
+    ) -> (SpsEqSignature<E, P>, Vec<P::Message>, ReprRandomness<E>)
+    where
+        R: Rng + CryptoRng,
+    {
+        let rnd_f = random_nonzero_scalar(rng);
+        let rnd_u = random_nonzero_scalar(rng);
 
-        (rnd_signature, rnd_message)
+        let rnd_signature = SpsEqSignature::<E, P>::rnd_signature(self, rnd_u, rnd_f);
+        let rnd_message = SpsEqSignature::<E, P>::rnd_message(message, rnd_f);
+
+        (rnd_signature, rnd_message, (rnd_f, rnd_u))
+    }
+
+    /// Generates the representation of the signature and message given by the randomness `f`,
+    /// which multiplies the message, and `psi`, which re-randomises the signature. Both must be
+    /// nonzero. As `change_repr`, the function does not check that the signature corresponds
+    /// to the message.
+    pub fn change_repr_with(
+        &self,
+        message: &[P::Message],
+        f: E::Fr,
+        psi: E::Fr,
+    ) -> Result<Representation<E, P>, SpsEqSignatureError> {
+        if f.is_zero() || psi.is_zero() {
+            return Err(SpsEqSignatureError::InvalidRandomness);
+        }
+
+        let rnd_signature = SpsEqSignature::<E, P>::rnd_signature(self, psi, f);
+        let rnd_message = SpsEqSignature::<E, P>::rnd_message(message, f);
+
+        Ok((rnd_signature, rnd_message))
+    }
+
+    /// Same as `change_repr`, but first verifies the signature over `message` under `public_key`,
+    /// as specified by `ChangeRepr`. If verification fails, the signature is left unchanged and
+    /// the verification error is returned.
+    pub fn change_repr_checked<R>(
+        &mut self,
+        message: &[P::Message],
+        public_key: &PublicKey<E, P>,
+        rng: &mut R,
+    ) -> Result<Vec<P::Message>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        public_key.verify(message, self)?;
+        Ok(self.change_repr(message, rng))
+    }
+
+    /// Same as `generate_new_repr`, but first verifies the signature over `message` under
+    /// `public_key`, as specified by `ChangeRepr`.
+    pub fn generate_new_repr_checked<R>(
+        self,
+        message: &[P::Message],
+        public_key: &PublicKey<E, P>,
+        rng: &mut R,
+    ) -> Result<Representation<E, P>, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        public_key.verify(message, &self)?;
+        Ok(self.generate_new_repr(message, rng))
+    }
+
+    /// Convert a `SpsEqSignature` to an array of bytes, using compressed points. This is the
+    /// encoding of `CanonicalSerialize::serialize`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut bytes = Vec::new();
+        self.serialize(&mut bytes)
+            .map_err(|_| SpsEqSignatureError::IoErrorWrite)?;
+        Ok(bytes)
+    }
+
+    /// Convert a `SpsEqSignature` to an array of bytes, using uncompressed points. This is the
+    /// encoding of `CanonicalSerialize::serialize_uncompressed`.
+    pub fn to_bytes_uncompressed(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut bytes = Vec::new();
+        self.serialize_uncompressed(&mut bytes)
+            .map_err(|_| SpsEqSignatureError::IoErrorWrite)?;
+        Ok(bytes)
+    }
+
+    fn encoded_size(compressed: bool) -> usize {
+        2 + 2 * point_size::<MessageAffine<E, P>>(compressed)
+            + point_size::<KeyAffine<E, P>>(compressed)
+    }
+
+    fn write_bytes(&self, compressed: bool) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut writer = Vec::new();
+        write_version(&mut writer);
+        write_point_encoding(&mut writer, compressed);
+        self.write_fields(&mut writer, compressed)?;
+        Ok(writer)
+    }
+
+    /// Writes the points of the signature, for signatures nested in other encodings
+    pub(crate) fn write_fields(
+        &self,
+        writer: &mut Vec<u8>,
+        compressed: bool,
+    ) -> Result<(), SpsEqSignatureError> {
+        for point in P::Message::batch_normalization_into_affine(&[self.Z, self.Y]) {
+            write_point(writer, &point, compressed)?;
+        }
+        write_point(writer, &self.Yp.into_affine(), compressed)
+    }
+
+    pub(crate) fn read_fields<R: Read>(
+        reader: &mut ByteReader<R>,
+        compressed: bool,
+    ) -> Result<Self, SpsEqSignatureError> {
+        let Z: MessageAffine<E, P> = reader.read_point(compressed)?;
+        let Y: MessageAffine<E, P> = reader.read_point(compressed)?;
+        let Yp: KeyAffine<E, P> = reader.read_point(compressed)?;
+
+        Ok(SpsEqSignature {
+            Z: Z.into_projective(),
+            Y: Y.into_projective(),
+            Yp: Yp.into_projective(),
+        })
     }
 
-    fn rnd_message(message: &[E::G1Projective], rnd_f: E::Fr) -> Vec<E::G1Projective> {
-        message
-            .to_owned()
-            .into_iter()
-            .map(|mut g| {
-                g *= rnd_f;
-                g
-            })
-            .collect()
+    /// Create a `SpsEqSignature` from an array of bytes, produced either by `to_bytes` or by
+    /// `to_bytes_uncompressed`. Points must lie in the prime-order subgroup and differ from the
+    /// identity; malformed input is reported as an error, and never panics. Decoding is shared
+    /// with `CanonicalDeserialize::deserialize`, which additionally accepts trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
+        let mut reader = ByteReader::new(bytes);
+        let signature = Self::read(&mut reader)?;
+        reader.finish()?;
+
+        Ok(signature)
+    }
+
+    fn read<R: Read>(reader: &mut ByteReader<R>) -> Result<Self, SpsEqSignatureError> {
+        reader.read_version()?;
+        let compressed = reader.read_point_encoding()?;
+        Self::read_fields(reader, compressed)
+    }
+
+    fn rnd_message(message: &[P::Message], rnd_f: E::Fr) -> Vec<P::Message> {
+        let rnd_f = rnd_f.into_repr();
+        message.iter().map(|g| g.mul(rnd_f)).collect()
     }
 
     fn rnd_signature(
-        signature: &SpsEqSignature<E>,
+        signature: &SpsEqSignature<E, P>,
         rnd_u: E::Fr,
         rnd_f: E::Fr,
-    ) -> SpsEqSignature<E> {
+    ) -> SpsEqSignature<E, P> {
+        // Callers sample nonzero randomness, or reject it
         let rnd_u_inverse = rnd_u.inverse().expect("It will never be zero");
 
         let mut rnd_signature = SpsEqSignature {
@@ -97,72 +246,215 @@ impl<E: PairingEngine> SpsEqSignature<E> {
     }
 }
 
+/// Canonical serialization of a `SpsEqSignature`, with the encoding of `to_bytes`
+impl<E: PairingEngine, P: Placement<E>> CanonicalSerialize for SpsEqSignature<E, P> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.write_bytes(true)?)?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        Self::encoded_size(true)
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.write_bytes(false)?)?)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        Self::encoded_size(false)
+    }
+}
+
+/// Canonical deserialization of a `SpsEqSignature`. The encoding states whether points are
+/// compressed, so every method accepts both; only `deserialize_unchecked` skips the checks
+/// on the points.
+impl<E: PairingEngine, P: Placement<E>> CanonicalDeserialize for SpsEqSignature<E, P> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::read(&mut ByteReader::from_reader(reader))?)
+    }
+
+    fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::read(
+            &mut ByteReader::from_reader(reader).unchecked(),
+        )?)
+    }
+}
+
 /// SPS-EQ signing key
 #[derive(Clone, Debug)]
-pub struct SigningKey<E: PairingEngine> {
+pub struct SigningKey<E: PairingEngine, P: Placement<E> = MessagesInG1> {
     /// Capacity supported by the signing key
     pub signature_capacity: usize,
     /// Secret keys
     secret_keys: Vec<E::Fr>,
+    placement: PhantomData<P>,
 }
 
-impl<E: PairingEngine> SigningKey<E> {
-    /// Generate a cryptographically random [`SigningKey`].
-    pub fn new<R>(signature_capacity: usize, rng: &mut R) -> SigningKey<E>
+impl<E: PairingEngine, P: Placement<E>> SigningKey<E, P> {
+    /// Generate a cryptographically random [`SigningKey`], with independent nonzero secret
+    /// keys for every slot.
+    pub fn new<R>(signature_capacity: usize, rng: &mut R) -> SigningKey<E, P>
     where
         R: Rng + CryptoRng,
     {
-        let secret_keys = vec![E::Fr::rand(rng); signature_capacity];
+        let secret_keys = (0..signature_capacity)
+            .map(|_| random_nonzero_scalar(rng))
+            .collect();
         SigningKey {
             signature_capacity,
             secret_keys,
+            placement: PhantomData,
         }
     }
 
-    /// Generate a [`SigningKey`] from a given input.
-    pub fn from(sks: Vec<E::Fr>) -> Result<SigningKey<E>, SpsEqSignatureError> {
+    /// Generate a [`SigningKey`] from a given input. The secret keys must be nonzero and
+    /// pairwise distinct: with equal keys, signatures also verify for the messages with the
+    /// corresponding components swapped.
+    pub fn from(sks: Vec<E::Fr>) -> Result<SigningKey<E, P>, SpsEqSignatureError> {
+        let has_duplicates = sks
+            .iter()
+            .enumerate()
+            .any(|(index, key)| sks[..index].contains(key));
+        if sks.is_empty() || sks.iter().any(|key| key.is_zero()) || has_duplicates {
+            return Err(SpsEqSignatureError::InvalidSecretKeyVector);
+        }
+
         let signature_capacity = sks.len();
 
         Ok(SigningKey {
             signature_capacity,
             secret_keys: sks,
+            placement: PhantomData,
         })
     }
 
-    /// Sign a message, represented by a tuple of elements of G1Projective
-    pub fn sign<R>(&self, messages: &[E::G1Projective], rng: &mut R) -> SpsEqSignature<E>
+    /// Convert a `SigningKey` to an array of bytes. This is the encoding of
+    /// `CanonicalSerialize::serialize`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut bytes = Vec::new();
+        self.serialize(&mut bytes)
+            .map_err(|_| SpsEqSignatureError::IoErrorWrite)?;
+        Ok(bytes)
+    }
+
+    fn write_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut writer = Vec::new();
+        write_version(&mut writer);
+        write_length(&mut writer, self.signature_capacity)?;
+        for key in self {
+            write_scalar(&mut writer, &key)?;
+        }
+        Ok(writer)
+    }
+
+    /// Create a `SigningKey` from an array of bytes produced by `to_bytes`. Decoding is shared
+    /// with `CanonicalDeserialize::deserialize`, which additionally accepts trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
+        let mut reader = ByteReader::new(bytes);
+        let signing_key = Self::read(&mut reader)?;
+        reader.finish()?;
+
+        Ok(signing_key)
+    }
+
+    fn read<R: Read>(reader: &mut ByteReader<R>) -> Result<Self, SpsEqSignatureError> {
+        reader.read_version()?;
+        let signature_capacity = reader.read_length(scalar_size::<E::Fr>())?;
+
+        // The length prefix is not checked against the size of a stream, so we do not
+        // preallocate from it
+        let mut secret_keys = Vec::new();
+        for _ in 0..signature_capacity {
+            secret_keys.push(reader.read_scalar()?);
+        }
+
+        if !reader.validate() {
+            return Ok(SigningKey {
+                signature_capacity,
+                secret_keys,
+                placement: PhantomData,
+            });
+        }
+        SigningKey::from(secret_keys)
+    }
+
+    /// Sign a message, represented by a tuple of elements of the message group. The message must
+    /// have exactly `signature_capacity` elements.
+    pub fn sign<R>(
+        &self,
+        messages: &[P::Message],
+        rng: &mut R,
+    ) -> Result<SpsEqSignature<E, P>, SpsEqSignatureError>
     where
         R: Rng + CryptoRng,
     {
         // todo: We probably want to do something when this goes out of scope
-        let mut randomness = E::Fr::rand(rng);
-        while randomness.is_zero() {
-            randomness = E::Fr::rand(rng);
+        let randomness: E::Fr = random_nonzero_scalar(rng);
+        self.sign_with_randomness(messages, randomness)
+    }
+
+    /// Same as `sign`, with the signing randomness `y` given by the caller, which must be
+    /// nonzero. Signatures sharing `y` share their `Y` and `Yp` points.
+    pub(crate) fn sign_with_randomness(
+        &self,
+        messages: &[P::Message],
+        randomness: E::Fr,
+    ) -> Result<SpsEqSignature<E, P>, SpsEqSignatureError> {
+        if messages.len() != self.signature_capacity {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
         }
 
-        let mut Z = E::G1Projective::zero();
-        let mut Y = E::G1Projective::prime_subgroup_generator();
-        let mut Yp = E::G2Projective::prime_subgroup_generator();
+        let mut Y = P::Message::prime_subgroup_generator();
+        let mut Yp = P::Key::prime_subgroup_generator();
 
-        // todo: in here we'll eventually use `VariableBaseMSM::multi_scalar_mul`. Not necessary
-        // yet, as we expect to have only two commitments.
-        let mut messages = messages.to_owned();
-        for (value, key) in messages.iter_mut().zip(self) {
-            *value *= key;
-            Z += *value;
-        }
+        // The randomness is folded into the scalars, so that Z = sum_i (y x_i) M_i is computed
+        // with a single multi-scalar multiplication.
+        let bases = P::Message::batch_normalization_into_affine(messages);
+        let scalars: Vec<_> = self
+            .secret_keys
+            .iter()
+            .map(|key| (*key * randomness).into_repr())
+            .collect();
+        let Z = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
 
-        Z *= randomness;
         Y *= randomness.inverse().expect("It will never be zero");
         Yp *= randomness.inverse().expect("It will never be zero");
 
-        SpsEqSignature { Z, Y, Yp }
+        Ok(SpsEqSignature { Z, Y, Yp })
+    }
+}
+
+/// Canonical serialization of a `SigningKey`, with the encoding of `to_bytes`
+impl<E: PairingEngine, P: Placement<E>> CanonicalSerialize for SigningKey<E, P> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        let mut bytes = self.write_bytes()?;
+        let result = writer.write_all(&bytes);
+        bytes.zeroize();
+        Ok(result?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        1 + 4 + self.signature_capacity * scalar_size::<E::Fr>()
+    }
+}
+
+/// Canonical deserialization of a `SigningKey`. `deserialize_unchecked` does not check that
+/// the secret keys are nonzero and pairwise distinct.
+impl<E: PairingEngine, P: Placement<E>> CanonicalDeserialize for SigningKey<E, P> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::read(&mut ByteReader::from_reader(reader))?)
+    }
+
+    fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::read(
+            &mut ByteReader::from_reader(reader).unchecked(),
+        )?)
     }
 }
 
 /// Implements `Zeroize` for SigningKeys.
 /// todo: probably not required, as E::FR already implements zeroize
-impl<E: PairingEngine> Zeroize for SigningKey<E> {
+impl<E: PairingEngine, P: Placement<E>> Zeroize for SigningKey<E, P> {
     fn zeroize(&mut self) {
         for key in self.secret_keys.iter_mut() {
             key.zeroize();
@@ -170,15 +462,15 @@ impl<E: PairingEngine> Zeroize for SigningKey<E> {
     }
 }
 
-impl<E: PairingEngine> PartialEq for SigningKey<E> {
+impl<E: PairingEngine, P: Placement<E>> PartialEq for SigningKey<E, P> {
     fn eq(&self, other: &Self) -> bool {
         self.secret_keys == other.secret_keys
     }
 }
 
-impl<'a, E: PairingEngine> IntoIterator for &'a SigningKey<E> {
+impl<'a, E: PairingEngine, P: Placement<E>> IntoIterator for &'a SigningKey<E, P> {
     type Item = E::Fr;
-    type IntoIter = KeyIntoIterator<'a, E>;
+    type IntoIter = KeyIntoIterator<'a, E, P>;
 
     fn into_iter(self) -> Self::IntoIter {
         KeyIntoIterator {
@@ -189,12 +481,12 @@ impl<'a, E: PairingEngine> IntoIterator for &'a SigningKey<E> {
 }
 
 /// Iterator for `SigningKey`, which implements `Iterator` itself
-pub struct KeyIntoIterator<'a, E: PairingEngine> {
-    signing_key: &'a SigningKey<E>,
+pub struct KeyIntoIterator<'a, E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    signing_key: &'a SigningKey<E, P>,
     index: usize,
 }
 
-impl<'a, E: PairingEngine> Iterator for KeyIntoIterator<'a, E> {
+impl<'a, E: PairingEngine, P: Placement<E>> Iterator for KeyIntoIterator<'a, E, P> {
     type Item = E::Fr;
 
     fn next(&mut self) -> Option<E::Fr> {
@@ -215,7 +507,7 @@ mod tests {
         Bls12_381, Fr, G1Affine, G1Projective as G1, G1Projective, G2Projective as G2,
     };
     use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
-    use ark_ff::{Field, One, Zero};
+    use ark_ff::{Field, One, UniformRand, Zero};
     use rand::thread_rng;
 
     #[test]
@@ -238,11 +530,42 @@ mod tests {
         assert_eq!(sk.secret_keys.len(), capacity);
     }
 
+    #[test]
+    fn test_new_keys_are_independent() {
+        let sk = SigningKey::<Bls12_381>::new(8, &mut thread_rng());
+
+        for (index, key) in sk.secret_keys.iter().enumerate() {
+            assert!(!key.is_zero());
+            assert!(!sk.secret_keys[..index].contains(key));
+        }
+    }
+
+    #[test]
+    fn test_from_rejects_insecure_keys() {
+        let zero_key = vec![Fr::one(), Fr::zero()];
+        assert_eq!(
+            SigningKey::<Bls12_381>::from(zero_key),
+            Err(SpsEqSignatureError::InvalidSecretKeyVector)
+        );
+
+        let duplicate_keys = vec![Fr::from(2u64), Fr::one(), Fr::from(2u64)];
+        assert_eq!(
+            SigningKey::<Bls12_381>::from(duplicate_keys),
+            Err(SpsEqSignatureError::InvalidSecretKeyVector)
+        );
+
+        assert_eq!(
+            SigningKey::<Bls12_381>::from(Vec::new()),
+            Err(SpsEqSignatureError::InvalidSecretKeyVector)
+        );
+    }
+
     #[test]
     fn test_iterator() {
-        let sk = SigningKey::<Bls12_381>::from(vec![Fr::one(); 3]).unwrap();
-        for item in &sk {
-            assert_eq!(item, Fr::one())
+        let keys = vec![Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)];
+        let sk = SigningKey::<Bls12_381>::from(keys.clone()).unwrap();
+        for (item, key) in (&sk).into_iter().zip(keys) {
+            assert_eq!(item, key)
         }
     }
 
@@ -255,6 +578,139 @@ mod tests {
         assert_eq!(sk, sk_from_value)
     }
 
+    #[test]
+    fn test_signing_key_from_to_bytes() {
+        let sk = SigningKey::<Bls12_381>::new(3, &mut thread_rng());
+
+        let bytes = sk.to_bytes().unwrap();
+        // version, length and three scalars
+        assert_eq!(bytes.len(), 1 + 4 + 3 * 32);
+        assert_eq!(SigningKey::from_bytes(&bytes).unwrap(), sk);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            SigningKey::<Bls12_381>::from_bytes(&trailing),
+            Err(SpsEqSignatureError::TrailingBytes)
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_unmatched_message_length() {
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+
+        let short_message = vec![G1::rand(&mut thread_rng())];
+        assert_eq!(
+            sk.sign(&short_message, &mut thread_rng()),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+
+        let long_message: Vec<G1> = (0..3).map(|_| G1::rand(&mut thread_rng())).collect();
+        assert_eq!(
+            sk.sign(&long_message, &mut thread_rng()),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+
+    #[test]
+    fn test_change_repr_with_randomness() {
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+        let pk = PublicKey::from(&sk);
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+        let signature = sk.sign(&message, &mut thread_rng()).unwrap();
+
+        let (new_signature, new_message, (f, psi)) =
+            signature.generate_new_repr_with_randomness(&message, &mut thread_rng());
+        assert!(pk.verify(&new_message, &new_signature).is_ok());
+        assert_eq!(
+            new_message,
+            vec![message[0].mul(f.into_repr()), message[1].mul(f.into_repr())]
+        );
+
+        // The exposed randomness reproduces the same representation
+        assert_eq!(
+            signature.change_repr_with(&message, f, psi),
+            Ok((new_signature, new_message))
+        );
+
+        assert_eq!(
+            signature.change_repr_with(&message, Fr::zero(), psi),
+            Err(SpsEqSignatureError::InvalidRandomness)
+        );
+        assert_eq!(
+            signature.change_repr_with(&message, f, Fr::zero()),
+            Err(SpsEqSignatureError::InvalidRandomness)
+        );
+    }
+
+    #[test]
+    fn test_signature_from_to_bytes() {
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+        let signature = sk.sign(&message, &mut thread_rng()).unwrap();
+
+        let bytes = signature.to_bytes().unwrap();
+        // version, point encoding, two compressed G1 points and a compressed G2 point
+        assert_eq!(bytes.len(), 1 + 1 + 2 * 48 + 96);
+        assert_eq!(SpsEqSignature::from_bytes(&bytes).unwrap(), signature);
+
+        let bytes = signature.to_bytes_uncompressed().unwrap();
+        assert_eq!(bytes.len(), 1 + 1 + 2 * 96 + 192);
+        assert_eq!(SpsEqSignature::from_bytes(&bytes).unwrap(), signature);
+
+        assert_eq!(
+            SpsEqSignature::<Bls12_381>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(SpsEqSignatureError::TruncatedInput)
+        );
+    }
+
+    #[test]
+    fn test_canonical_serialization() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(2, rng);
+        let message = vec![G1::rand(rng), G1::rand(rng)];
+        let signature = sk.sign(&message, rng).unwrap();
+
+        // The signatures and keys nest in larger canonical structures
+        let mut bytes = Vec::new();
+        (signature.clone(), sk.clone())
+            .serialize(&mut bytes)
+            .unwrap();
+        assert_eq!(
+            bytes.len(),
+            signature.serialized_size() + sk.serialized_size()
+        );
+        assert_eq!(
+            bytes[..signature.serialized_size()],
+            signature.to_bytes().unwrap()[..]
+        );
+        let (decoded_signature, decoded_sk) =
+            <(SpsEqSignature<Bls12_381>, SigningKey<Bls12_381>)>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(decoded_signature, signature);
+        assert_eq!(decoded_sk, sk);
+
+        let mut bytes = Vec::new();
+        vec![signature.clone(), signature.clone()]
+            .serialize_uncompressed(&mut bytes)
+            .unwrap();
+        let decoded = Vec::<SpsEqSignature<Bls12_381>>::deserialize_uncompressed(&bytes[..]);
+        assert_eq!(decoded.unwrap(), vec![signature.clone(), signature.clone()]);
+        let decoded = Vec::<SpsEqSignature<Bls12_381>>::deserialize_unchecked(&bytes[..]);
+        assert_eq!(decoded.unwrap(), vec![signature.clone(), signature]);
+
+        // Only the unchecked mode accepts insecure keys
+        let mut bytes = Vec::new();
+        SigningKey::<Bls12_381> {
+            signature_capacity: 2,
+            secret_keys: vec![Fr::one(), Fr::one()],
+            placement: PhantomData,
+        }
+        .serialize(&mut bytes)
+        .unwrap();
+        assert!(SigningKey::<Bls12_381>::deserialize(&bytes[..]).is_err());
+        assert!(SigningKey::<Bls12_381>::deserialize_unchecked(&bytes[..]).is_ok());
+    }
+
     #[test]
     fn test_addition() {
         let mut init = G2::prime_subgroup_generator();
@@ -270,8 +726,6 @@ mod tests {
         let _Y = G1::prime_subgroup_generator();
         let _Yp = G2::prime_subgroup_generator();
 
-        // todo: multi scalar mult is not of utter importance now. We only expect two mults. Change
-        // eventually
         let a = VariableBaseMSM::multi_scalar_mul(&[Z], &[Fr::one().into()]);
 
         let b = Fr::rand(&mut thread_rng());
@@ -0,0 +1,360 @@
+//! Module describing tag-based equivalence class signatures, which bind a tag, such as an
+//! epoch or a revocation handle, to a signed message.
+//!
+//! A tag $\tau\in\mathbb Z_p^*$ is represented in the message group of the placement, which
+//! is $\mathbb{G}_1$ by default, by a pair $T = (T_1, T_2) = (g_1^f, g_1^{f\tau})$. A message
+//! $M\in(\mathbb{G}_1^*)^l$ with tag $T$ is signed as the SPS-EQ message $(M, T_1, T_2)$ of
+//! length $l + 2$, with key $(x_1, \ldots, x_l, u_1, u_2)$. Alongside the SPS-EQ signature
+//! $(Z, Y_1, Y_2)$, the signer outputs $Z_T = y(u_1T_1 + u_2T_2)$, the part of $Z$ that signs
+//! the tag. $(Z_T, Y_1, Y_2)$ is itself an SPS-EQ signature on $T$ under $(u_1, u_2)$, and
+//! shares its randomness $y$ with the signature on the whole vector.
+//!
+//! [`change_repr`] multiplies the message and the tag by the same random $f$, so that the new
+//! representative is unlinkable to the original one under DDH. [`change_tag_repr`] only moves
+//! the tag to a fresh representative $T^{f'}$, replacing $Z$ by $Z + (f' - 1)Z_T$ and $Z_T$ by
+//! $f'Z_T$. In both cases the signature is re-randomised, and the tag still satisfies
+//! $T_2 = T_1^\tau$. Anyone knowing $\tau$ can check the tag.
+//!
+//! The tag stays bound to the message: the two verification equations only hold for $Z$ and
+//! $Z_T$ computed with the same $y$, which is fresh for each signature, so neither a tag
+//! representing a different $\tau$ nor the tag part of another signature can be combined with
+//! the message.
+
+use alloc::vec::Vec;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, Zero};
+
+use crate::errors::*;
+use crate::placement::*;
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::utils::random_nonzero_scalar;
+use crate::verify::PublicKey;
+use rand::{CryptoRng, Rng};
+
+/// Representative of a tag
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Base of the tag, `f` times the generator of the message group
+    pub base: P::Message,
+    /// Value of the tag, `tau` times the base
+    pub value: P::Message,
+}
+
+impl<E: PairingEngine, P: Placement<E>> Tag<E, P> {
+    /// Creates the canonical representative of the tag `tau`, which must be nonzero
+    pub fn new(tau: E::Fr) -> Result<Self, SpsEqSignatureError> {
+        if tau.is_zero() {
+            return Err(SpsEqSignatureError::InvalidTag);
+        }
+
+        let base = P::Message::prime_subgroup_generator();
+        Ok(Tag {
+            base,
+            value: base.mul(tau.into_repr()),
+        })
+    }
+
+    /// Checks whether the tag represents `tau`
+    pub fn matches(&self, tau: E::Fr) -> bool {
+        self.base.mul(tau.into_repr()) == self.value
+    }
+
+    fn to_message(&self) -> Vec<P::Message> {
+        vec![self.base, self.value]
+    }
+
+    fn with_message(&self, message: &[P::Message]) -> Vec<P::Message> {
+        let mut tagged_message = message.to_vec();
+        tagged_message.push(self.base);
+        tagged_message.push(self.value);
+        tagged_message
+    }
+
+    fn scale(&self, f: E::Fr) -> Self {
+        let f = f.into_repr();
+        Tag {
+            base: self.base.mul(f),
+            value: self.value.mul(f),
+        }
+    }
+}
+
+/// Signature over a tagged message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagSignature<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// SPS-EQ signature over the message followed by the tag
+    pub signature: SpsEqSignature<E, P>,
+    /// Part of the `Z` point of the signature that signs the tag
+    pub tag_component: P::Message,
+}
+
+impl<E: PairingEngine, P: Placement<E>> TagSignature<E, P> {
+    fn tag_signature(&self) -> SpsEqSignature<E, P> {
+        SpsEqSignature {
+            Z: self.tag_component,
+            Y: self.signature.Y,
+            Yp: self.signature.Yp,
+        }
+    }
+
+    /// Moves the tag part of the signature to the tag multiplied by `f`, and the message part to
+    /// the message multiplied by `mu`, then re-randomises the signature with `psi`
+    fn adapt(&self, mu: E::Fr, f: E::Fr, psi: E::Fr) -> Self {
+        let psi_inverse = psi.inverse().expect("It will never be zero");
+        let message_component = self.signature.Z - self.tag_component;
+        let tag_component = self.tag_component.mul((f * psi).into_repr());
+
+        TagSignature {
+            signature: SpsEqSignature {
+                Z: message_component.mul((mu * psi).into_repr()) + tag_component,
+                Y: self.signature.Y.mul(psi_inverse.into_repr()),
+                Yp: self.signature.Yp.mul(psi_inverse.into_repr()),
+            },
+            tag_component,
+        }
+    }
+}
+
+/// Representative of a tagged message, together with its tag and signature
+pub type TaggedRepresentation<E, P = MessagesInG1> = (
+    Vec<<P as Placement<E>>::Message>,
+    Tag<E, P>,
+    TagSignature<E, P>,
+);
+
+/// Tag of a message, together with the signature over both
+pub type TaggedSignature<E, P = MessagesInG1> = (Tag<E, P>, TagSignature<E, P>);
+
+/// Signs a message with the tag `tau`. The signing key must have capacity `message.len() + 2`,
+/// and its last two keys sign the tag.
+pub fn sign<E, P, R>(
+    signing_key: &SigningKey<E, P>,
+    message: &[P::Message],
+    tau: E::Fr,
+    rng: &mut R,
+) -> Result<TaggedSignature<E, P>, SpsEqSignatureError>
+where
+    E: PairingEngine,
+    P: Placement<E>,
+    R: Rng + CryptoRng,
+{
+    let tag = Tag::new(tau)?;
+    let randomness = random_nonzero_scalar(rng);
+    let signature = signing_key.sign_with_randomness(&tag.with_message(message), randomness)?;
+
+    let tag_key = SigningKey::<E, P>::from(signing_key.into_iter().skip(message.len()).collect())?;
+    let tag_signature = tag_key.sign_with_randomness(&tag.to_message(), randomness)?;
+
+    Ok((
+        tag,
+        TagSignature {
+            signature,
+            tag_component: tag_signature.Z,
+        },
+    ))
+}
+
+/// Verifies a signature over a message and a tag, without knowledge of the tag itself
+pub fn verify<E: PairingEngine, P: Placement<E>>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    tag: &Tag<E, P>,
+    signature: &TagSignature<E, P>,
+) -> Result<(), SpsEqSignatureError> {
+    public_key.verify(&tag.with_message(message), &signature.signature)?;
+
+    let tag_key = PublicKey::from_public_keys(public_key.into_iter().skip(message.len()).collect());
+    tag_key.verify(&tag.to_message(), &signature.tag_signature())
+}
+
+/// Verifies a signature over a message and checks that its tag represents `tau`
+pub fn verify_with_tag<E: PairingEngine, P: Placement<E>>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    tag: &Tag<E, P>,
+    signature: &TagSignature<E, P>,
+    tau: E::Fr,
+) -> Result<(), SpsEqSignatureError> {
+    verify(public_key, message, tag, signature)?;
+    if !tag.matches(tau) {
+        return Err(SpsEqSignatureError::InvalidTag);
+    }
+    Ok(())
+}
+
+/// Changes the representation of a tagged message and its signature, which is verified first.
+/// The message and the tag move to the same new representative, and the new tag represents the
+/// same tag as the original one.
+pub fn change_repr<E, P, R>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    tag: &Tag<E, P>,
+    signature: &TagSignature<E, P>,
+    rng: &mut R,
+) -> Result<TaggedRepresentation<E, P>, SpsEqSignatureError>
+where
+    E: PairingEngine,
+    P: Placement<E>,
+    R: Rng + CryptoRng,
+{
+    verify(public_key, message, tag, signature)?;
+
+    let f: E::Fr = random_nonzero_scalar(rng);
+    let psi = random_nonzero_scalar(rng);
+    let message = message.iter().map(|m| m.mul(f.into_repr())).collect();
+    Ok((message, tag.scale(f), signature.adapt(f, f, psi)))
+}
+
+/// Changes the representation of the tag alone, and adapts its signature, which is verified
+/// first. The message is unchanged, and the new tag represents the same tag as the original one.
+pub fn change_tag_repr<E, P, R>(
+    public_key: &PublicKey<E, P>,
+    message: &[P::Message],
+    tag: &Tag<E, P>,
+    signature: &TagSignature<E, P>,
+    rng: &mut R,
+) -> Result<TaggedSignature<E, P>, SpsEqSignatureError>
+where
+    E: PairingEngine,
+    P: Placement<E>,
+    R: Rng + CryptoRng,
+{
+    verify(public_key, message, tag, signature)?;
+
+    let f = random_nonzero_scalar(rng);
+    let psi = random_nonzero_scalar(rng);
+    Ok((tag.scale(f), signature.adapt(E::Fr::one(), f, psi)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_tagged_signature() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(4, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        let epoch = Fr::from(7u64);
+        let (tag, signature) = sign(&sk, &message, epoch, rng).unwrap();
+        assert!(verify_with_tag(&pk, &message, &tag, &signature, epoch).is_ok());
+
+        let (new_message, new_tag, new_signature) =
+            change_repr(&pk, &message, &tag, &signature, rng).unwrap();
+        assert_ne!(new_tag, tag);
+        assert_ne!(new_message, message);
+        assert!(verify_with_tag(&pk, &new_message, &new_tag, &new_signature, epoch).is_ok());
+
+        assert_eq!(
+            verify_with_tag(&pk, &new_message, &new_tag, &new_signature, Fr::from(8u64)),
+            Err(SpsEqSignatureError::InvalidTag)
+        );
+    }
+
+    #[test]
+    fn test_tag_cannot_be_changed() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message = vec![G1::rand(rng)];
+        let (tag, signature) = sign(&sk, &message, Fr::from(7u64), rng).unwrap();
+
+        let other_tag = Tag::new(Fr::from(8u64)).unwrap();
+        assert_eq!(
+            verify(&pk, &message, &other_tag, &signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+        assert_eq!(
+            change_repr(&pk, &message, &other_tag, &signature, rng),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+        assert!(change_repr(&pk, &message, &tag, &signature, rng).is_ok());
+        assert_eq!(
+            change_tag_repr(&pk, &message, &other_tag, &signature, rng),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+
+        // Another representative of the same tag is not bound to the message
+        let scaled_tag = tag.scale(Fr::rand(rng));
+        assert!(scaled_tag.matches(Fr::from(7u64)));
+        assert_eq!(
+            verify(&pk, &message, &scaled_tag, &signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+
+        assert_eq!(
+            sign(&sk, &message, Fr::zero(), rng),
+            Err(SpsEqSignatureError::InvalidTag)
+        );
+        // The signing key must account for the two tag components
+        assert_eq!(
+            sign(&sk, &[G1::rand(rng), G1::rand(rng)], Fr::from(7u64), rng),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+
+    #[test]
+    fn test_change_tag_repr() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(4, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+        let epoch = Fr::from(7u64);
+        let (tag, signature) = sign(&sk, &message, epoch, rng).unwrap();
+
+        let (new_tag, new_signature) =
+            change_tag_repr(&pk, &message, &tag, &signature, rng).unwrap();
+        assert_ne!(new_tag, tag);
+        assert_ne!(new_signature, signature);
+        assert!(verify_with_tag(&pk, &message, &new_tag, &new_signature, epoch).is_ok());
+        assert_eq!(
+            verify(&pk, &message, &tag, &new_signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+
+        // Both changes compose, moving the message and the tag to independent representatives
+        let (new_message, newer_tag, newer_signature) =
+            change_repr(&pk, &message, &new_tag, &new_signature, rng).unwrap();
+        assert!(verify_with_tag(&pk, &new_message, &newer_tag, &newer_signature, epoch).is_ok());
+    }
+
+    #[test]
+    fn test_tag_part_is_bound_to_its_signature() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message = vec![G1::rand(rng)];
+        let (tag, signature) = sign(&sk, &message, Fr::from(7u64), rng).unwrap();
+        let (other_tag, other_signature) = sign(&sk, &message, Fr::from(8u64), rng).unwrap();
+
+        // The tag part of another signature does not verify with this one
+        let mixed_signature = TagSignature {
+            signature: signature.signature.clone(),
+            tag_component: other_signature.tag_component,
+        };
+        assert_eq!(
+            verify(&pk, &message, &tag, &mixed_signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+        assert_eq!(
+            verify(&pk, &message, &other_tag, &mixed_signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+
+        // Moving the tag without adapting the signature is rejected
+        let mut stale_signature = signature.clone();
+        stale_signature.tag_component *= Fr::from(2u64);
+        assert_eq!(
+            verify(&pk, &message, &tag.scale(Fr::from(2u64)), &stale_signature),
+            Err(SpsEqSignatureError::InvalidSignature)
+        );
+    }
+}
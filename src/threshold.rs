@@ -0,0 +1,723 @@
+//! Module describing threshold issuance of SPS-EQ signatures by $t$-out-of-$n$ signers, none of
+//! which ever holds the signing key.
+//!
+//! The signers hold Shamir shares $\left[x_i\right]_j$ of degree $t - 1$ of every secret key
+//! $x_i$, as produced by the [distributed key generation](crate::dkg), or by a trusted dealer
+//! sharing an existing key with [`ThresholdKey::share`]. The [`ThresholdKey`]
+//! publishes the joint public key together with the verification keys
+//! $X_{j, i} = \left[x_i\right]_j g_2$ of every signer $j$.
+//!
+//! A signature $(Z, Y_1, Y_2) = (y\sum_i x_i M_i, \frac{1}{y}g_1, \frac{1}{y}g_2)$ also needs a
+//! randomness $y$ that no signer may learn, since $y$ and $Z$ reveal $\sum_i x_i M_i$. The
+//! signers generate it ahead of time as a [`Presignature`], with the inversion trick of Bar-Ilan
+//! and Beaver: running the rounds of the key generation, they jointly share random nonces $r$
+//! and $s$ with polynomials of degree $t - 1$, and two sharings $\rho$ and $\sigma$ of zero with
+//! polynomials of degree $2t - 2$. The randomness is $y = \frac{1}{s}$, so that $Y_2 = s g_2$ is
+//! the commitment to the constant term of the sharing of $s$.
+//!
+//! To sign a message $M$, signer $j$ publishes the partial signature
+//!
+//! \begin{equation}
+//!     R_j = \left[r\right]_j g_1 \hspace{0.5cm}
+//!     T_j = \left[s\right]_j g_1 \hspace{0.5cm}
+//!     w_j = \left[r\right]_j\left[s\right]_j + \left[\sigma\right]_j \hspace{0.5cm}
+//!     V_{j, i} = \left[r\right]_j\left[x_i\right]_j g_2 \hspace{0.5cm}
+//!     Z_j = \left[r\right]_j\sum_i\left[x_i\right]_j M_i + \left[\rho\right]_j g_1,
+//! \end{equation}
+//!
+//! which is checked with pairings against the verification keys and the commitments of the
+//! presignature. The values $w_j$ and $Z_j$ are shares of degree $2t - 2$ of $w = rs$ and
+//! $r\sum_i x_i M_i$, masked by the sharings of zero. Any $2t - 1$ valid partial signatures
+//! therefore combine with Lagrange coefficients into $Z = \frac{1}{w}\sum_j\lambda_j Z_j$ and
+//! $Y_1 = \sum_j\lambda_j T_j$, an ordinary [`SpsEqSignature`] under the joint public key.
+//! Signing needs $2t - 1 \le n$ signers, while fewer than $t$ signers learn nothing about the
+//! key vector.
+//!
+//! A presignature must sign a single message, as two signatures sharing $y$ combine into a
+//! signature over the sum of their messages. Signing therefore consumes the
+//! [`PresignatureShare`], which can be neither cloned nor printed.
+
+use alloc::vec::Vec;
+use ark_ec::{msm::VariableBaseMSM, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use core::marker::PhantomData;
+use rand::{CryptoRng, Rng};
+use zeroize::Zeroize;
+
+use crate::dkg::{Envelope, KeyShare, Participant, Sharing, Transport};
+use crate::errors::*;
+use crate::placement::*;
+use crate::sign::{SigningKey, SpsEqSignature};
+use crate::utils::{
+    evaluate_commitments, evaluate_polynomial, is_in_prime_order_subgroup, lagrange_coefficient,
+};
+use crate::verify::PublicKey;
+
+/// Public presignature together with the share of a signer
+pub type SharedPresignature<E, P = MessagesInG1> = (Presignature<E, P>, PresignatureShare<E, P>);
+
+/// Public key of the signers: the joint public key and the verification keys of every signer.
+///
+/// The key tolerates up to `threshold - 1` corrupted signers, which learn nothing about the
+/// key vector. Combining a signature needs `2 threshold - 1` valid partial signatures, since
+/// they are shares of degree `2 threshold - 2`, so at least `2 threshold - 1` of the `signers`
+/// must take part in every signature. For instance, with 5 signers, a threshold of 3 tolerates
+/// 2 corrupted signers and needs all 5 to sign, while a threshold of 2 needs 3 signers and only
+/// tolerates 1 corrupted signer.
+#[derive(Debug, PartialEq)]
+pub struct ThresholdKey<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Number of signers whose shares determine the signing key
+    pub threshold: usize,
+    /// Joint public key, verifying the combined signatures
+    pub public_key: PublicKey<E, P>,
+    /// Verification keys `[x_i]_j g2` of every signer `j`
+    verification_keys: Vec<Vec<P::Key>>,
+}
+
+/// Public part of a presignature: the `Yp` point of the signature and the commitments to the
+/// sharings of the nonces
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Presignature<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Yp point of the signature
+    pub Yp: P::Key,
+    r_commitments: Vec<P::Key>,
+    s_commitments: Vec<P::Key>,
+    rho_commitments: Vec<P::Key>,
+    sigma_commitments: Vec<P::Key>,
+}
+
+/// Share of a presignature held by a signer. It must sign a single message, so it can be
+/// neither cloned nor printed, and signing consumes it:
+///
+/// ```compile_fail,E0382
+/// # use ark_bls12_381::{Bls12_381, G1Projective as G1};
+/// # use sps_eq::dkg::KeyShare;
+/// # use sps_eq::threshold::PresignatureShare;
+/// # fn sign_twice(
+/// #     share: PresignatureShare<Bls12_381>,
+/// #     key_share: &KeyShare<Bls12_381>,
+/// #     message: &[G1],
+/// # ) {
+/// let partial = share.sign(key_share, message);
+/// let other_partial = share.sign(key_share, message);
+/// # }
+/// ```
+pub struct PresignatureShare<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Index of the signer, starting at 1
+    pub index: usize,
+    r: E::Fr,
+    s: E::Fr,
+    rho: E::Fr,
+    sigma: E::Fr,
+    placement: PhantomData<P>,
+}
+
+/// Partial signature of a signer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialSignature<E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    /// Index of the signer, starting at 1
+    pub index: usize,
+    /// Share of `r` times the Z point, masked by the sharing of zero `rho`
+    pub Z: P::Message,
+    /// Share `[r]_j g1` of the nonce `r`
+    pub R: P::Message,
+    /// Share `[s]_j g1` of the Y point
+    pub T: P::Message,
+    /// Share `[r]_j [s]_j + [sigma]_j` of the product `w = r s`
+    pub w: E::Fr,
+    /// Products `[r]_j [x_i]_j g2` of the nonce and the verification keys
+    pub V: Vec<P::Key>,
+}
+
+impl<E: PairingEngine, P: Placement<E>> ThresholdKey<E, P> {
+    /// Builds the key of `signers` signers from the commitments `C_{i, m} = a_{i, m} g2` to the
+    /// coefficients of the polynomials `a_i` sharing every secret key `x_i = a_i(0)`. The
+    /// function fails if a public key `X_i = C_{i, 0}` is the identity or equal to another one,
+    /// as the secret keys would no longer be independent.
+    pub(crate) fn from_commitments(
+        commitments: &[Vec<P::Key>],
+        signers: usize,
+    ) -> Result<Self, SpsEqSignatureError> {
+        let public_keys: Vec<P::Key> = commitments
+            .iter()
+            .map(|coefficients| coefficients[0])
+            .collect();
+        let has_invalid_component = public_keys
+            .iter()
+            .enumerate()
+            .any(|(i, key)| key.is_zero() || public_keys[..i].iter().any(|other| other == key));
+        if has_invalid_component {
+            return Err(SpsEqSignatureError::InvalidPublicKey);
+        }
+        let verification_keys = (1..=signers)
+            .map(|j| {
+                commitments
+                    .iter()
+                    .map(|coefficients| evaluate_commitments(coefficients, j))
+                    .collect()
+            })
+            .collect();
+
+        Ok(ThresholdKey {
+            threshold: commitments[0].len(),
+            public_key: PublicKey::from_public_keys(public_keys),
+            verification_keys,
+        })
+    }
+
+    /// Shares an existing signing key among `signers` signers with threshold `threshold`, as a
+    /// trusted dealer. Signatures combined by the signers verify under the public key of
+    /// `signing_key`, which is consumed and erased. The dealer knows the key, so the signers
+    /// must trust it to erase any other copy. Fails if `threshold` is zero or exceeds
+    /// `signers`.
+    pub fn share<R>(
+        mut signing_key: SigningKey<E, P>,
+        threshold: usize,
+        signers: usize,
+        rng: &mut R,
+    ) -> Result<(Self, Vec<KeyShare<E, P>>), SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if threshold == 0 || threshold > signers {
+            return Err(SpsEqSignatureError::InvalidParameters);
+        }
+
+        let mut polynomials: Vec<Vec<E::Fr>> = signing_key
+            .into_iter()
+            .map(|key| {
+                let mut coefficients = vec![key];
+                coefficients.extend((1..threshold).map(|_| E::Fr::rand(rng)));
+                coefficients
+            })
+            .collect();
+        signing_key.zeroize();
+
+        let generator = P::Key::prime_subgroup_generator();
+        let commitments: Vec<Vec<P::Key>> = polynomials
+            .iter()
+            .map(|coefficients| {
+                coefficients
+                    .iter()
+                    .map(|coefficient| generator.mul(coefficient.into_repr()))
+                    .collect()
+            })
+            .collect();
+        let key_shares = (1..=signers)
+            .map(|j| {
+                let shares = polynomials
+                    .iter()
+                    .map(|coefficients| evaluate_polynomial(coefficients, j))
+                    .collect();
+                KeyShare::new(j, shares)
+            })
+            .collect();
+        polynomials.iter_mut().flatten().for_each(Zeroize::zeroize);
+
+        Ok((Self::from_commitments(&commitments, signers)?, key_shares))
+    }
+
+    /// Number of signers holding shares of the key
+    pub fn signers(&self) -> usize {
+        self.verification_keys.len()
+    }
+
+    /// Number of partial signatures needed to sign, `2 threshold - 1`
+    pub fn signing_threshold(&self) -> usize {
+        2 * self.threshold - 1
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> Participant<E, P> {
+    /// Creates the signer with index `index`, starting at 1, of the generation of a
+    /// presignature under `key`. The generation runs the rounds of the key generation, and
+    /// fails if there are fewer than `2 threshold - 1` signers.
+    pub fn new_presigner<R>(
+        index: usize,
+        key: &ThresholdKey<E, P>,
+        rng: &mut R,
+    ) -> Result<Self, SpsEqSignatureError>
+    where
+        R: Rng + CryptoRng,
+    {
+        if key.signing_threshold() > key.signers() {
+            return Err(SpsEqSignatureError::InvalidParameters);
+        }
+
+        let nonce = Sharing {
+            coefficients: key.threshold,
+            zero_constant: false,
+        };
+        let mask = Sharing {
+            coefficients: key.signing_threshold(),
+            zero_constant: true,
+        };
+        Participant::with_sharings(
+            index,
+            key.threshold,
+            key.signers(),
+            vec![nonce, nonce, mask, mask],
+            rng,
+        )
+    }
+
+    /// Checks the answers to the complaints, and computes the presignature and the share of
+    /// the signer
+    pub fn finish_presignature(
+        self,
+        incoming: Vec<Envelope<E, P>>,
+    ) -> Result<SharedPresignature<E, P>, SpsEqSignatureError> {
+        let sharing = self.finish_sharing(incoming)?;
+        let mut commitments = sharing.commitments;
+        let presignature = Presignature {
+            Yp: commitments[1][0],
+            sigma_commitments: commitments.remove(3),
+            rho_commitments: commitments.remove(2),
+            s_commitments: commitments.remove(1),
+            r_commitments: commitments.remove(0),
+        };
+        let mut shares = sharing.shares;
+        let share = PresignatureShare {
+            index: sharing.index,
+            r: shares[0],
+            s: shares[1],
+            rho: shares[2],
+            sigma: shares[3],
+            placement: PhantomData,
+        };
+        shares.zeroize();
+        Ok((presignature, share))
+    }
+
+    /// Runs the six rounds of the generation of a presignature over `transport`
+    pub fn run_presignature<T>(
+        mut self,
+        transport: &mut T,
+    ) -> Result<SharedPresignature<E, P>, SpsEqSignatureError>
+    where
+        T: Transport<E, P>,
+    {
+        let incoming = self.exchange_rounds(transport)?;
+        self.finish_presignature(incoming)
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> PresignatureShare<E, P> {
+    /// Computes the partial signature over `message` with the key share of the signer,
+    /// consuming the share of the presignature
+    pub fn sign(
+        self,
+        key_share: &KeyShare<E, P>,
+        message: &[P::Message],
+    ) -> Result<PartialSignature<E, P>, SpsEqSignatureError> {
+        if key_share.index != self.index {
+            return Err(SpsEqSignatureError::InvalidParameters);
+        }
+        if message.len() != key_share.shares().len() {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+
+        // [r]_j [x_i]_j for every key
+        let mut products: Vec<E::Fr> = key_share
+            .shares()
+            .iter()
+            .map(|share| self.r * share)
+            .collect();
+        let generator = P::Message::prime_subgroup_generator();
+        let key_generator = P::Key::prime_subgroup_generator();
+        let bases = P::Message::batch_normalization_into_affine(message);
+        let scalars: Vec<_> = products.iter().map(|product| product.into_repr()).collect();
+
+        let partial = PartialSignature {
+            index: self.index,
+            Z: VariableBaseMSM::multi_scalar_mul(&bases, &scalars)
+                + generator.mul(self.rho.into_repr()),
+            R: generator.mul(self.r.into_repr()),
+            T: generator.mul(self.s.into_repr()),
+            w: self.r * self.s + self.sigma,
+            V: scalars
+                .iter()
+                .map(|&scalar| key_generator.mul(scalar))
+                .collect(),
+        };
+        products.zeroize();
+        Ok(partial)
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> Drop for PresignatureShare<E, P> {
+    fn drop(&mut self) {
+        self.r.zeroize();
+        self.s.zeroize();
+        self.rho.zeroize();
+        self.sigma.zeroize();
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> Presignature<E, P> {
+    /// Verifies the partial signature of a signer over `message`
+    pub fn verify_partial(
+        &self,
+        key: &ThresholdKey<E, P>,
+        message: &[P::Message],
+        partial: &PartialSignature<E, P>,
+    ) -> Result<(), SpsEqSignatureError> {
+        let index = partial.index;
+        if index == 0 || index > key.signers() {
+            return Err(SpsEqSignatureError::InvalidSignature);
+        }
+        let verification_keys = &key.verification_keys[index - 1];
+        if message.len() != verification_keys.len() || partial.V.len() != message.len() {
+            return Err(SpsEqSignatureError::UnmatchedCapacity);
+        }
+        if message.iter().any(|point| point.is_zero()) {
+            return Err(SpsEqSignatureError::IdentityMessageComponent);
+        }
+        let points_in_subgroup = [partial.Z, partial.R, partial.T]
+            .iter()
+            .all(is_in_prime_order_subgroup)
+            && partial.V.iter().all(is_in_prime_order_subgroup);
+        if !points_in_subgroup {
+            return Err(SpsEqSignatureError::PointNotInSubgroup);
+        }
+
+        let r_key = evaluate_commitments(&self.r_commitments, index);
+        let s_key = evaluate_commitments(&self.s_commitments, index);
+        let rho_key = evaluate_commitments(&self.rho_commitments, index);
+        let sigma_key = evaluate_commitments(&self.sigma_commitments, index);
+        let g1 = P::Message::prime_subgroup_generator();
+        let g2 = P::Key::prime_subgroup_generator();
+
+        // e(R_j, g2) = e(g1, [r]_j g2), e(T_j, g2) = e(g1, [s]_j g2),
+        // e(R_j, [s]_j g2) e(g1, [sigma]_j g2) = e(w_j g1, g2) and e(R_j, X_{j, i}) = e(g1, V_{j, i})
+        let nonces_are_valid = pairings_are_one::<E, P>(&[(partial.R, g2), (-g1, r_key)])
+            && pairings_are_one::<E, P>(&[(partial.T, g2), (-g1, s_key)])
+            && pairings_are_one::<E, P>(&[
+                (partial.R, s_key),
+                (g1, sigma_key),
+                (-g1.mul(partial.w.into_repr()), g2),
+            ])
+            && verification_keys
+                .iter()
+                .zip(&partial.V)
+                .all(|(&x, &v)| pairings_are_one::<E, P>(&[(partial.R, x), (-g1, v)]));
+
+        // e(M_1, V_{j, 1}) ... e(M_l, V_{j, l}) e(g1, [rho]_j g2) = e(Z_j, g2)
+        let mut pairs: Vec<(P::Message, P::Key)> = message
+            .iter()
+            .copied()
+            .zip(partial.V.iter().copied())
+            .collect();
+        pairs.push((g1, rho_key));
+        pairs.push((-partial.Z, g2));
+        if !nonces_are_valid || !pairings_are_one::<E, P>(&pairs) {
+            return Err(SpsEqSignatureError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Combines the partial signatures over `message` into a signature. Invalid partial
+    /// signatures and repeated signers are skipped, and the function fails if fewer than
+    /// `2 threshold - 1` valid ones remain.
+    pub fn combine(
+        &self,
+        key: &ThresholdKey<E, P>,
+        message: &[P::Message],
+        partials: &[PartialSignature<E, P>],
+    ) -> Result<SpsEqSignature<E, P>, SpsEqSignatureError> {
+        let needed = key.signing_threshold();
+        let mut valid: Vec<&PartialSignature<E, P>> = Vec::with_capacity(needed);
+        for partial in partials {
+            if valid.len() == needed {
+                break;
+            }
+            if valid.iter().all(|other| other.index != partial.index)
+                && self.verify_partial(key, message, partial).is_ok()
+            {
+                valid.push(partial);
+            }
+        }
+        if valid.len() < needed {
+            return Err(SpsEqSignatureError::NotEnoughShares);
+        }
+
+        let indices: Vec<usize> = valid.iter().map(|partial| partial.index).collect();
+        let mut w = E::Fr::zero();
+        let mut Z = P::Message::zero();
+        let mut Y = P::Message::zero();
+        for partial in valid {
+            let lambda = lagrange_coefficient::<E::Fr>(&indices, partial.index);
+            w += lambda * partial.w;
+            Z += &partial.Z.mul(lambda.into_repr());
+            Y += &partial.T.mul(lambda.into_repr());
+        }
+        // w = r s is zero only if one of the nonces is
+        let w_inverse = w.inverse().ok_or(SpsEqSignatureError::InvalidRandomness)?;
+
+        Ok(SpsEqSignature {
+            Z: Z.mul(w_inverse.into_repr()),
+            Y,
+            Yp: self.Yp,
+        })
+    }
+}
+
+/// Checks that the product of the pairings of the given message and key group points is one
+fn pairings_are_one<E: PairingEngine, P: Placement<E>>(pairs: &[(P::Message, P::Key)]) -> bool {
+    let (points, keys): (Vec<P::Message>, Vec<P::Key>) = pairs.iter().copied().unzip();
+    let pairs: Vec<(E::G1Prepared, E::G2Prepared)> =
+        P::Message::batch_normalization_into_affine(&points)
+            .into_iter()
+            .zip(P::Key::batch_normalization_into_affine(&keys))
+            .map(|(point, key)| P::pair(P::prepare_message(point), P::prepare_key(key)))
+            .collect();
+    E::product_of_pairings(&pairs).is_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::DkgOutput;
+    use ark_bls12_381::{Bls12_381, G1Projective as G1, G2Projective as G2};
+    use rand::thread_rng;
+
+    type Party<P> = Participant<Bls12_381, P>;
+    /// Party with the messages of the last round, ready to finish
+    type Finishing<P> = (Party<P>, Vec<Envelope<Bls12_381, P>>);
+
+    /// Runs the rounds of the parties in lockstep, returning every party with the messages of
+    /// the last round
+    fn run_in_lockstep<P: Placement<Bls12_381>>(mut parties: Vec<Party<P>>) -> Vec<Finishing<P>> {
+        let deliver = |outgoing: &[Envelope<Bls12_381, P>], index: usize| -> Vec<_> {
+            outgoing
+                .iter()
+                .filter(|envelope| {
+                    envelope.sender != index && envelope.recipient.unwrap_or(index) == index
+                })
+                .cloned()
+                .collect()
+        };
+
+        let outgoing: Vec<_> = parties.iter_mut().flat_map(|party| party.deal()).collect();
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|party| {
+                let incoming = deliver(&outgoing, party.index());
+                party.complain(incoming)
+            })
+            .collect();
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|party| {
+                let incoming = deliver(&outgoing, party.index());
+                party.respond(incoming)
+            })
+            .collect();
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|party| {
+                let incoming = deliver(&outgoing, party.index());
+                party.extract(incoming)
+            })
+            .collect();
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|party| {
+                let incoming = deliver(&outgoing, party.index());
+                party.accuse(incoming)
+            })
+            .collect();
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|party| {
+                let incoming = deliver(&outgoing, party.index());
+                party.reveal(incoming)
+            })
+            .collect();
+        parties
+            .into_iter()
+            .map(|party| {
+                let incoming = deliver(&outgoing, party.index());
+                (party, incoming)
+            })
+            .collect()
+    }
+
+    /// Generates a key shared among `signers` signers with the distributed key generation
+    fn generate_key<P: Placement<Bls12_381>>(
+        threshold: usize,
+        signers: usize,
+        capacity: usize,
+    ) -> (ThresholdKey<Bls12_381, P>, Vec<KeyShare<Bls12_381, P>>) {
+        let rng = &mut thread_rng();
+        let parties = (1..=signers)
+            .map(|index| Participant::new(index, threshold, signers, capacity, rng).unwrap())
+            .collect();
+        let outputs: Vec<DkgOutput<Bls12_381, P>> = run_in_lockstep(parties)
+            .into_iter()
+            .map(|(party, incoming)| party.finish(incoming).unwrap())
+            .collect();
+
+        let mut key = None;
+        let key_shares = outputs
+            .into_iter()
+            .map(|output| {
+                key.get_or_insert(output.threshold_key);
+                output.key_share
+            })
+            .collect();
+        (key.unwrap(), key_shares)
+    }
+
+    /// Generates a presignature among the signers `signers`
+    fn presign<P: Placement<Bls12_381>>(
+        key: &ThresholdKey<Bls12_381, P>,
+        signers: &[usize],
+    ) -> (
+        Presignature<Bls12_381, P>,
+        Vec<PresignatureShare<Bls12_381, P>>,
+    ) {
+        let rng = &mut thread_rng();
+        let parties = signers
+            .iter()
+            .map(|&index| Participant::new_presigner(index, key, rng).unwrap())
+            .collect();
+
+        let mut presignatures = Vec::new();
+        let shares = run_in_lockstep(parties)
+            .into_iter()
+            .map(|(party, incoming)| {
+                let (presignature, share) = party.finish_presignature(incoming).unwrap();
+                presignatures.push(presignature);
+                share
+            })
+            .collect();
+        // All signers agree on the presignature
+        assert!(presignatures.iter().all(|other| *other == presignatures[0]));
+        (presignatures.remove(0), shares)
+    }
+
+    #[test]
+    fn test_threshold_signature() {
+        let rng = &mut thread_rng();
+        let (key, key_shares) = generate_key::<MessagesInG1>(2, 4, 3);
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+
+        // Any 3 of the 4 signers can presign and sign
+        for signers in [[1usize, 2, 3], [4, 2, 1], [2, 3, 4]].iter() {
+            let (presignature, shares) = presign(&key, signers);
+            let partials: Vec<_> = shares
+                .into_iter()
+                .map(|share| {
+                    let key_share = &key_shares[share.index - 1];
+                    share.sign(key_share, &message).unwrap()
+                })
+                .collect();
+            for partial in &partials {
+                assert!(presignature.verify_partial(&key, &message, partial).is_ok());
+            }
+
+            let signature = presignature.combine(&key, &message, &partials).unwrap();
+            assert!(key.public_key.verify_strict(&message, &signature).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_misbehaving_signer_is_identified() {
+        let rng = &mut thread_rng();
+        let (key, key_shares) = generate_key::<MessagesInG1>(2, 4, 2);
+        let message: Vec<G1> = (0..2).map(|_| G1::rand(rng)).collect();
+
+        let (presignature, shares) = presign(&key, &[1, 2, 3, 4]);
+        let mut partials: Vec<_> = shares
+            .into_iter()
+            .zip(&key_shares)
+            .map(|(share, key_share)| share.sign(key_share, &message).unwrap())
+            .collect();
+
+        let mut tampered = partials.clone();
+        tampered[0].Z += G1::prime_subgroup_generator();
+        tampered[1].w += <Bls12_381 as PairingEngine>::Fr::one();
+        tampered[2].V[1] += G2::prime_subgroup_generator();
+        tampered[3].T = tampered[2].T;
+        for partial in &tampered {
+            assert_eq!(
+                presignature.verify_partial(&key, &message, partial),
+                Err(SpsEqSignatureError::InvalidSignature)
+            );
+        }
+
+        // The invalid partial signature is skipped when combining
+        partials[1] = tampered[1].clone();
+        let signature = presignature.combine(&key, &message, &partials).unwrap();
+        assert!(key.public_key.verify(&message, &signature).is_ok());
+
+        // Without enough valid partial signatures, combining fails
+        let repeated = [
+            partials[0].clone(),
+            partials[2].clone(),
+            partials[0].clone(),
+        ];
+        assert_eq!(
+            presignature.combine(&key, &message, &repeated),
+            Err(SpsEqSignatureError::NotEnoughShares)
+        );
+
+        // A share of a presignature only signs with the key share of the same signer
+        let (_, mut shares) = presign(&key, &[1, 2, 3]);
+        assert_eq!(
+            shares.remove(0).sign(&key_shares[1], &message).unwrap_err(),
+            SpsEqSignatureError::InvalidParameters
+        );
+    }
+
+    #[test]
+    fn test_threshold_signature_in_g2() {
+        let rng = &mut thread_rng();
+        let (key, key_shares) = generate_key::<MessagesInG2>(2, 3, 2);
+        let message: Vec<G2> = (0..2).map(|_| G2::rand(rng)).collect();
+
+        let (presignature, shares) = presign(&key, &[1, 2, 3]);
+        let partials: Vec<_> = shares
+            .into_iter()
+            .zip(&key_shares)
+            .map(|(share, key_share)| share.sign(key_share, &message).unwrap())
+            .collect();
+        let signature = presignature.combine(&key, &message, &partials).unwrap();
+        assert!(key.public_key.verify(&message, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_threshold() {
+        // With a threshold of 3, signing needs 5 signers
+        let (key, _) = generate_key::<MessagesInG1>(3, 4, 2);
+        assert_eq!(
+            Participant::new_presigner(1, &key, &mut thread_rng()).unwrap_err(),
+            SpsEqSignatureError::InvalidParameters
+        );
+    }
+
+    #[test]
+    fn test_threshold_signature_with_shared_key() {
+        let rng = &mut thread_rng();
+        let signing_key = SigningKey::<Bls12_381>::new(3, rng);
+        let public_key = PublicKey::from(&signing_key);
+        let (key, key_shares) = ThresholdKey::share(signing_key, 2, 3, rng).unwrap();
+        assert_eq!(key.public_key, public_key);
+
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let (presignature, shares) = presign(&key, &[1, 2, 3]);
+        let partials: Vec<_> = shares
+            .into_iter()
+            .zip(&key_shares)
+            .map(|(share, key_share)| share.sign(key_share, &message).unwrap())
+            .collect();
+        let signature = presignature.combine(&key, &message, &partials).unwrap();
+        assert!(public_key.verify_strict(&message, &signature).is_ok());
+
+        assert_eq!(
+            ThresholdKey::share(SigningKey::<Bls12_381>::new(3, rng), 4, 3, rng).unwrap_err(),
+            SpsEqSignatureError::InvalidParameters
+        );
+    }
+}
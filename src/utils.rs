@@ -0,0 +1,80 @@
+//! Group and polynomial helpers shared across modules
+
+use alloc::vec::Vec;
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField};
+use rand::Rng;
+
+/// Checks whether `point` belongs to the prime-order subgroup, by multiplying it with the order
+/// of the scalar field.
+pub(crate) fn is_in_prime_order_subgroup<G: ProjectiveCurve>(point: &G) -> bool {
+    point.mul(G::ScalarField::characteristic()).is_zero()
+}
+
+/// Samples a uniformly random nonzero scalar
+pub(crate) fn random_nonzero_scalar<F: Field, R: Rng>(rng: &mut R) -> F {
+    loop {
+        let scalar = F::rand(rng);
+        if !scalar.is_zero() {
+            return scalar;
+        }
+    }
+}
+
+/// Evaluates the polynomial with the given coefficients, constant term first, at `index`
+pub(crate) fn evaluate_polynomial<F: PrimeField>(coefficients: &[F], index: usize) -> F {
+    let point = F::from(index as u64);
+    coefficients
+        .iter()
+        .rev()
+        .fold(F::zero(), |value, coefficient| value * point + coefficient)
+}
+
+/// Evaluates at `index`, in the exponent, the polynomial whose coefficients are committed in
+/// `commitments`, constant term first
+pub(crate) fn evaluate_commitments<G: ProjectiveCurve>(commitments: &[G], index: usize) -> G {
+    let point = G::ScalarField::from(index as u64).into_repr();
+    commitments
+        .iter()
+        .rev()
+        .fold(G::zero(), |value, commitment| value.mul(point) + commitment)
+}
+
+/// Lagrange coefficient at zero of the signer `index` among the signers `indices`
+pub(crate) fn lagrange_coefficient<F: PrimeField>(indices: &[usize], index: usize) -> F {
+    let point = F::from(index as u64);
+    let (numerator, denominator) = indices
+        .iter()
+        .filter(|&&other| other != index)
+        .map(|&other| F::from(other as u64))
+        .fold((F::one(), F::one()), |(numerator, denominator), other| {
+            (numerator * other, denominator * (other - point))
+        });
+    numerator * denominator.inverse().expect("Indices are distinct")
+}
+
+/// Coefficients, constant term first, of the polynomial of degree less than `points.len()`
+/// through the points `(index, value)`, whose indices must be distinct
+pub(crate) fn interpolate_polynomial<F: PrimeField>(points: &[(usize, F)]) -> Vec<F> {
+    let mut coefficients = vec![F::zero(); points.len()];
+    for &(index, value) in points {
+        // Lagrange basis polynomial prod_{other != index} (X - other) / (index - other)
+        let point = F::from(index as u64);
+        let mut basis = vec![F::one()];
+        let mut denominator = F::one();
+        for &(other, _) in points.iter().filter(|(other, _)| *other != index) {
+            let other = F::from(other as u64);
+            basis.insert(0, F::zero());
+            for m in 0..basis.len() - 1 {
+                let next = basis[m + 1];
+                basis[m] -= other * next;
+            }
+            denominator *= point - other;
+        }
+        let scale = value * denominator.inverse().expect("Indices are distinct");
+        for (coefficient, term) in coefficients.iter_mut().zip(basis) {
+            *coefficient += scale * term;
+        }
+    }
+    coefficients
+}
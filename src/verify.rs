@@ -1,111 +1,407 @@
 //! Module describing the verifying procedures and structs
-use ark_ec::{PairingEngine, ProjectiveCurve};
+use alloc::vec::Vec;
+use ark_ec::{msm::FixedBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 
 use crate::errors::*;
+use crate::placement::*;
+use crate::serialization::*;
 use crate::sign::{SigningKey, SpsEqSignature};
-use ark_ff::{FromBytes, ToBytes};
-use std::convert::TryInto;
+use crate::utils::is_in_prime_order_subgroup;
+use rand::{CryptoRng, Rng};
 
-/// SPS-EQ public key
+/// Message representative together with its signature, as an entry of a batch to verify
+pub type SignedMessage<'a, E, P = MessagesInG1> =
+    (&'a [<P as Placement<E>>::Message], &'a SpsEqSignature<E, P>);
+
+/// SPS-EQ public key, with keys in the group given by the placement `P`
 #[derive(Debug)]
-pub struct PublicKey<E: PairingEngine> {
+pub struct PublicKey<E: PairingEngine, P: Placement<E> = MessagesInG1> {
     /// Capacity supported by the signing key
     pub signature_capacity: usize,
     /// Public keys
-    public_keys: Vec<E::G2Projective>,
+    public_keys: Vec<P::Key>,
+    /// Public keys prepared for the Miller loop, computed once and reused across verifications
+    prepared_keys: Vec<P::PreparedKey>,
 }
 
-impl<E: PairingEngine> PublicKey<E> {
-    /// Verify a signature with the public key
+impl<E: PairingEngine, P: Placement<E>> PublicKey<E, P> {
+    pub(crate) fn from_public_keys(public_keys: Vec<P::Key>) -> Self {
+        let prepared_keys = P::Key::batch_normalization_into_affine(&public_keys)
+            .into_iter()
+            .map(P::prepare_key)
+            .collect();
+
+        PublicKey {
+            signature_capacity: public_keys.len(),
+            public_keys,
+            prepared_keys,
+        }
+    }
+
+    /// Verify a signature with the public key. Message components and signature points must
+    /// differ from the identity.
     pub fn verify(
         &self,
-        messages: &[E::G1Projective],
-        signature: &SpsEqSignature<E>,
+        messages: &[P::Message],
+        signature: &SpsEqSignature<E, P>,
+    ) -> Result<(), SpsEqSignatureError> {
+        self.check_well_formed(messages, signature)?;
+        self.check_pairing_equations(messages, signature)
+    }
+
+    /// Verify a signature with the public key, additionally checking that the message
+    /// components and signature points lie in the prime-order subgroup. Use this variant for
+    /// points obtained from untrusted sources without going through the `from_bytes` helpers,
+    /// which already perform the check.
+    pub fn verify_strict(
+        &self,
+        messages: &[P::Message],
+        signature: &SpsEqSignature<E, P>,
     ) -> Result<(), SpsEqSignatureError> {
-        if self.signature_capacity != messages.len() {
+        self.check_well_formed(messages, signature)?;
+        self.check_subgroups(messages, signature)?;
+        self.check_pairing_equations(messages, signature)
+    }
+
+    fn check_well_formed(
+        &self,
+        messages: &[P::Message],
+        signature: &SpsEqSignature<E, P>,
+    ) -> Result<(), SpsEqSignatureError> {
+        if messages.is_empty() || self.signature_capacity != messages.len() {
             return Err(SpsEqSignatureError::UnmatchedCapacity);
         }
 
-        let mut check_1 = E::pairing(messages[0], self.public_keys[0]);
-        for (&message, key) in messages.iter().zip(self.public_keys.clone()).skip(1) {
-            check_1 *= &E::pairing(message, key);
+        if messages.iter().any(|message| message.is_zero()) {
+            return Err(SpsEqSignatureError::IdentityMessageComponent);
+        }
+
+        // Y and Yp are elements of G^*, and rejecting an identity Z rules out trivial signatures
+        if signature.Z.is_zero() || signature.Y.is_zero() || signature.Yp.is_zero() {
+            return Err(SpsEqSignatureError::IdentitySignatureComponent);
+        }
+
+        Ok(())
+    }
+
+    fn check_subgroups(
+        &self,
+        messages: &[P::Message],
+        signature: &SpsEqSignature<E, P>,
+    ) -> Result<(), SpsEqSignatureError> {
+        let message_points_in_subgroup = messages
+            .iter()
+            .chain(&[signature.Z, signature.Y])
+            .all(is_in_prime_order_subgroup);
+        if !message_points_in_subgroup || !is_in_prime_order_subgroup(&signature.Yp) {
+            return Err(SpsEqSignatureError::PointNotInSubgroup);
         }
+        Ok(())
+    }
 
-        let expected_check_1 = E::pairing(signature.Z, signature.Yp);
+    fn check_pairing_equations(
+        &self,
+        messages: &[P::Message],
+        signature: &SpsEqSignature<E, P>,
+    ) -> Result<(), SpsEqSignatureError> {
+        // Both equations are checked as a product of pairings equal to one, so that each of
+        // them costs a single final exponentiation. The equations are written for messages in
+        // G1, and `P::pair` orders the arguments of each pairing for the placement.
+        let mut message_points = messages.to_vec();
+        message_points.push(-signature.Z);
+        message_points.push(signature.Y);
+        let message_points = P::Message::batch_normalization_into_affine(&message_points);
+        let prepared_yp = P::prepare_key(signature.Yp.into_affine());
 
-        if check_1 != expected_check_1 {
+        // e(M_1, X_1) ... e(M_l, X_l) e(-Z, Yp) = 1
+        let check_1: Vec<(E::G1Prepared, E::G2Prepared)> = message_points[..=messages.len()]
+            .iter()
+            .zip(self.prepared_keys.iter().chain(Some(&prepared_yp)))
+            .map(|(&point, key)| P::pair(P::prepare_message(point), key.clone()))
+            .collect();
+        if !E::product_of_pairings(&check_1).is_one() {
             return Err(SpsEqSignatureError::InvalidSignature);
         }
 
-        let check_2 = E::pairing(signature.Y, E::G2Projective::prime_subgroup_generator());
-        let expected_check_2 =
-            E::pairing(E::G1Projective::prime_subgroup_generator(), signature.Yp);
-        if check_2 != expected_check_2 {
+        // e(Y, g2) e(-g1, Yp) = 1
+        let check_2 = [
+            P::pair(
+                P::prepare_message(message_points[messages.len() + 1]),
+                P::prepare_key(KeyAffine::<E, P>::prime_subgroup_generator()),
+            ),
+            P::pair(
+                P::prepare_message(-MessageAffine::<E, P>::prime_subgroup_generator()),
+                prepared_yp,
+            ),
+        ];
+        if !E::product_of_pairings(&check_2).is_one() {
             return Err(SpsEqSignatureError::InvalidSignature);
         }
 
         Ok(())
     }
 
-    // todo: handle the to_bytes/from_bytes -> likely to get mismatches in different architectures
-    /// Convert a `PublicKey` to an array of bytes
-    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
-        let mut writer = self.signature_capacity.to_be_bytes().to_vec();
-        for point in self {
-            let write = point.write(&mut writer);
-            match write {
-                Ok(_) => (),
-                Err(_) => return Err(SpsEqSignatureError::IoErrorWrite),
+    /// Verify a batch of signatures with the public key. Each entry is first checked as in
+    /// `verify_strict`, including the subgroup checks, since the bound below does not hold for
+    /// points outside the prime-order subgroup. The pairing equations of the remaining signatures
+    /// are combined with random 64-bit exponents into a single product of pairings, which holds
+    /// for an invalid batch with probability at most 2^-64. If it does not hold, the batch is
+    /// bisected to locate the invalid signatures, and their indices are returned in increasing
+    /// order.
+    pub fn batch_verify<R>(
+        &self,
+        batch: &[SignedMessage<'_, E, P>],
+        rng: &mut R,
+    ) -> Result<(), Vec<usize>>
+    where
+        R: Rng + CryptoRng,
+    {
+        let mut invalid = Vec::new();
+        // Malformed entries and points outside the subgroup are rejected upfront, and left out of
+        // the combined equations
+        let well_formed: Vec<usize> = (0..batch.len())
+            .filter(|&index| {
+                let (messages, signature) = batch[index];
+                let is_well_formed = self
+                    .check_well_formed(messages, signature)
+                    .and_then(|_| self.check_subgroups(messages, signature))
+                    .is_ok();
+                if !is_well_formed {
+                    invalid.push(index);
+                }
+                is_well_formed
+            })
+            .collect();
+
+        self.bisect_batch(batch, &well_formed, rng, &mut invalid);
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            invalid.sort_unstable();
+            Err(invalid)
+        }
+    }
+
+    fn bisect_batch<R>(
+        &self,
+        batch: &[SignedMessage<'_, E, P>],
+        indices: &[usize],
+        rng: &mut R,
+        invalid: &mut Vec<usize>,
+    ) where
+        R: Rng + CryptoRng,
+    {
+        match indices {
+            [] => (),
+            [index] => {
+                let (messages, signature) = batch[*index];
+                if self.check_pairing_equations(messages, signature).is_err() {
+                    invalid.push(*index);
+                }
+            }
+            _ => {
+                if !self.check_combined_equations(batch, indices, rng) {
+                    let (left, right) = indices.split_at(indices.len() / 2);
+                    self.bisect_batch(batch, left, rng, invalid);
+                    self.bisect_batch(batch, right, rng, invalid);
+                }
             }
         }
-        Ok(writer.to_vec())
     }
 
-    /// Create a `PublicKey` from an array of bytes
+    /// Checks the pairing equations of the signatures at `indices`, combined with random
+    /// exponents d_j and e_j:
+    ///
+    /// e(sum_j d_j M_j1, X_1) ... e(sum_j d_j M_jl, X_l) e(sum_j e_j Y_j, g2)
+    ///     prod_j e(-d_j Z_j - e_j g1, Yp_j) = 1
+    fn check_combined_equations<R>(
+        &self,
+        batch: &[SignedMessage<'_, E, P>],
+        indices: &[usize],
+        rng: &mut R,
+    ) -> bool
+    where
+        R: Rng + CryptoRng,
+    {
+        let mut combined_messages = vec![P::Message::zero(); self.signature_capacity];
+        let mut combined_y = P::Message::zero();
+        let mut signature_points = Vec::with_capacity(indices.len());
+        let mut yps = Vec::with_capacity(indices.len());
+
+        for &index in indices {
+            let (messages, signature) = batch[index];
+            let exponent_1 = [random_exponent(rng)];
+            let exponent_2 = [random_exponent(rng)];
+
+            for (combined, message) in combined_messages.iter_mut().zip(messages) {
+                *combined += &message.mul(exponent_1);
+            }
+            combined_y += &signature.Y.mul(exponent_2);
+            signature_points.push(
+                -(signature.Z.mul(exponent_1)
+                    + P::Message::prime_subgroup_generator().mul(exponent_2)),
+            );
+            yps.push(signature.Yp);
+        }
+
+        combined_messages.push(combined_y);
+        combined_messages.extend(signature_points);
+        let message_points = P::Message::batch_normalization_into_affine(&combined_messages);
+        let yps = P::Key::batch_normalization_into_affine(&yps);
+
+        let keys = self
+            .prepared_keys
+            .iter()
+            .cloned()
+            .chain(Some(P::prepare_key(
+                KeyAffine::<E, P>::prime_subgroup_generator(),
+            )))
+            .chain(yps.into_iter().map(P::prepare_key));
+        let pairs: Vec<(E::G1Prepared, E::G2Prepared)> = message_points
+            .into_iter()
+            .map(P::prepare_message)
+            .zip(keys)
+            .map(|(point, key)| P::pair(point, key))
+            .collect();
+
+        E::product_of_pairings(&pairs).is_one()
+    }
+
+    /// Convert a `PublicKey` to an array of bytes, using compressed points. This is the
+    /// encoding of `CanonicalSerialize::serialize`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut bytes = Vec::new();
+        self.serialize(&mut bytes)
+            .map_err(|_| SpsEqSignatureError::IoErrorWrite)?;
+        Ok(bytes)
+    }
+
+    /// Convert a `PublicKey` to an array of bytes, using uncompressed points. This is the
+    /// encoding of `CanonicalSerialize::serialize_uncompressed`.
+    pub fn to_bytes_uncompressed(&self) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut bytes = Vec::new();
+        self.serialize_uncompressed(&mut bytes)
+            .map_err(|_| SpsEqSignatureError::IoErrorWrite)?;
+        Ok(bytes)
+    }
+
+    fn encoded_size(&self, compressed: bool) -> usize {
+        2 + 4 + self.signature_capacity * point_size::<KeyAffine<E, P>>(compressed)
+    }
+
+    fn write_bytes(&self, compressed: bool) -> Result<Vec<u8>, SpsEqSignatureError> {
+        let mut writer = Vec::new();
+        write_version(&mut writer);
+        write_point_encoding(&mut writer, compressed);
+        write_length(&mut writer, self.signature_capacity)?;
+        for key in P::Key::batch_normalization_into_affine(&self.public_keys) {
+            write_point(&mut writer, &key, compressed)?;
+        }
+        Ok(writer)
+    }
+
+    /// Create a `PublicKey` from an array of bytes, produced either by `to_bytes` or by
+    /// `to_bytes_uncompressed`. Keys must lie in the prime-order subgroup and differ from the
+    /// identity; malformed input is reported as an error, and never panics. Decoding is shared
+    /// with `CanonicalDeserialize::deserialize`, which additionally accepts trailing bytes.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpsEqSignatureError> {
-        let signature_capacity = usize::from_be_bytes(bytes[..8].try_into().expect("Handle this"));
+        let mut reader = ByteReader::new(bytes);
+        let public_key = Self::read(&mut reader)?;
+        reader.finish()?;
+
+        Ok(public_key)
+    }
+
+    fn read<R: Read>(reader: &mut ByteReader<R>) -> Result<Self, SpsEqSignatureError> {
+        reader.read_version()?;
+        let compressed = reader.read_point_encoding()?;
+        let signature_capacity = reader.read_length(point_size::<KeyAffine<E, P>>(compressed))?;
+
+        // The length prefix is not checked against the size of a stream, so we do not
+        // preallocate from it
         let mut public_keys = Vec::new();
-        // todo: these values should not be hardcoded - should come from PairingEngine
-        for keys in bytes[8..].chunks(288) {
-            public_keys.push(E::G2Projective::read(keys).expect("and this"));
+        for _ in 0..signature_capacity {
+            let key: KeyAffine<E, P> = reader.read_point(compressed)?;
+            public_keys.push(key.into_projective());
         }
 
-        if signature_capacity != public_keys.len() {
-            return Err(SpsEqSignatureError::UnmatchedCapacity);
-        }
+        Ok(PublicKey::from_public_keys(public_keys))
+    }
+}
 
-        Ok(PublicKey {
-            signature_capacity,
-            public_keys,
-        })
+/// Canonical serialization of a `PublicKey`, with the encoding of `to_bytes`
+impl<E: PairingEngine, P: Placement<E>> CanonicalSerialize for PublicKey<E, P> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.write_bytes(true)?)?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.encoded_size(true)
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.write_bytes(false)?)?)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.encoded_size(false)
     }
 }
 
-/// Generate public keys from a secret key
-impl<'a, E: PairingEngine> From<&SigningKey<E>> for PublicKey<E> {
-    fn from(signing_key: &SigningKey<E>) -> PublicKey<E> {
-        let signature_capacity = signing_key.signature_capacity;
+/// Canonical deserialization of a `PublicKey`. The encoding states whether points are
+/// compressed, so every method accepts both; only `deserialize_unchecked` skips the checks
+/// on the keys.
+impl<E: PairingEngine, P: Placement<E>> CanonicalDeserialize for PublicKey<E, P> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::read(&mut ByteReader::from_reader(reader))?)
+    }
 
-        let mut public_keys = vec![E::G2Projective::prime_subgroup_generator(); signature_capacity];
-        for (pkey, skey) in public_keys.iter_mut().zip(signing_key) {
-            *pkey *= skey;
-        }
-        PublicKey {
-            signature_capacity,
-            public_keys,
+    fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::read(
+            &mut ByteReader::from_reader(reader).unchecked(),
+        )?)
+    }
+}
+
+/// Samples a nonzero exponent for the random linear combination of batch verification
+fn random_exponent<R: Rng>(rng: &mut R) -> u64 {
+    loop {
+        let exponent = rng.gen();
+        if exponent != 0 {
+            return exponent;
         }
     }
 }
 
-impl<E: PairingEngine> PartialEq for PublicKey<E> {
+/// Generate public keys from a secret key
+impl<E: PairingEngine, P: Placement<E>> From<&SigningKey<E, P>> for PublicKey<E, P> {
+    fn from(signing_key: &SigningKey<E, P>) -> PublicKey<E, P> {
+        let secret_keys: Vec<E::Fr> = signing_key.into_iter().collect();
+
+        // All keys are multiples of the same generator, so we use a precomputed window table
+        let scalar_size = E::Fr::size_in_bits();
+        let window = FixedBaseMSM::get_mul_window_size(secret_keys.len());
+        let table =
+            FixedBaseMSM::get_window_table(scalar_size, window, P::Key::prime_subgroup_generator());
+        let public_keys =
+            FixedBaseMSM::multi_scalar_mul::<P::Key>(scalar_size, window, &table, &secret_keys);
+
+        PublicKey::from_public_keys(public_keys)
+    }
+}
+
+impl<E: PairingEngine, P: Placement<E>> PartialEq for PublicKey<E, P> {
     fn eq(&self, other: &Self) -> bool {
         self.public_keys == other.public_keys
     }
 }
 
-impl<'a, E: PairingEngine> IntoIterator for &'a PublicKey<E> {
-    type Item = E::G2Projective;
-    type IntoIter = PubKeyIntoIterator<'a, E>;
+impl<'a, E: PairingEngine, P: Placement<E>> IntoIterator for &'a PublicKey<E, P> {
+    type Item = P::Key;
+    type IntoIter = PubKeyIntoIterator<'a, E, P>;
 
     fn into_iter(self) -> Self::IntoIter {
         PubKeyIntoIterator {
@@ -116,15 +412,15 @@ impl<'a, E: PairingEngine> IntoIterator for &'a PublicKey<E> {
 }
 
 /// Iterator for `PublicKey`, which implements `Iterator` itself
-pub struct PubKeyIntoIterator<'a, E: PairingEngine> {
-    public_key: &'a PublicKey<E>,
+pub struct PubKeyIntoIterator<'a, E: PairingEngine, P: Placement<E> = MessagesInG1> {
+    public_key: &'a PublicKey<E, P>,
     index: usize,
 }
 
-impl<'a, E: PairingEngine> Iterator for PubKeyIntoIterator<'a, E> {
-    type Item = E::G2Projective;
+impl<'a, E: PairingEngine, P: Placement<E>> Iterator for PubKeyIntoIterator<'a, E, P> {
+    type Item = P::Key;
 
-    fn next(&mut self) -> Option<E::G2Projective> {
+    fn next(&mut self) -> Option<P::Key> {
         match self.public_key.public_keys.get(self.index) {
             Some(x) => {
                 self.index += 1;
@@ -139,34 +435,246 @@ impl<'a, E: PairingEngine> Iterator for PubKeyIntoIterator<'a, E> {
 mod tests {
     use super::*;
 
-    use ark_bls12_381::{Bls12_381, G1Projective as G1};
-    use ark_ff::UniformRand;
-    use rand::thread_rng;
+    use ark_bls12_381::{Bls12_381, G1Affine, G1Projective as G1, G2Projective as G2};
+    use ark_ff::{UniformRand, Zero};
+    use rand::{thread_rng, Rng};
 
     #[test]
     fn test_from_to_bytes() {
         let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
         let pk = PublicKey::from(&sk);
 
-        let mut bytes_pk = pk.to_bytes().unwrap();
+        let bytes_pk = pk.to_bytes().unwrap();
+        // version, point encoding, length and two compressed G2 points
+        assert_eq!(bytes_pk.len(), 1 + 1 + 4 + 2 * 96);
+
+        let pk_from_bytes = PublicKey::from_bytes(&bytes_pk).unwrap();
+        assert_eq!(pk, pk_from_bytes);
 
-        let pk_from_bytes = PublicKey::from_bytes(&mut bytes_pk).unwrap();
+        let bytes_pk = pk.to_bytes_uncompressed().unwrap();
+        assert_eq!(bytes_pk.len(), 1 + 1 + 4 + 2 * 192);
 
+        let pk_from_bytes = PublicKey::from_bytes(&bytes_pk).unwrap();
         assert_eq!(pk, pk_from_bytes);
     }
+
+    #[test]
+    fn test_canonical_serialization() {
+        let rng = &mut thread_rng();
+        let pk = PublicKey::from(&SigningKey::<Bls12_381>::new(3, rng));
+
+        let mut bytes = Vec::new();
+        pk.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes, pk.to_bytes().unwrap());
+        assert_eq!(bytes.len(), pk.serialized_size());
+        // Trailing bytes belong to the enclosing structure
+        bytes.push(0);
+        assert_eq!(PublicKey::deserialize(&bytes[..]).unwrap(), pk);
+
+        let mut bytes = Vec::new();
+        pk.serialize_uncompressed(&mut bytes).unwrap();
+        assert_eq!(bytes, pk.to_bytes_uncompressed().unwrap());
+        assert_eq!(bytes.len(), pk.uncompressed_size());
+        assert_eq!(PublicKey::deserialize_uncompressed(&bytes[..]).unwrap(), pk);
+        assert_eq!(PublicKey::deserialize_unchecked(&bytes[..]).unwrap(), pk);
+
+        // A point outside of the curve is only accepted by the unchecked mode
+        bytes[6 + 100] ^= 1;
+        assert!(PublicKey::<Bls12_381>::deserialize_uncompressed(&bytes[..]).is_err());
+        assert!(PublicKey::<Bls12_381>::deserialize_unchecked(&bytes[..]).is_ok());
+        assert!(PublicKey::<Bls12_381>::deserialize(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_malformed_input() {
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+        let pk = PublicKey::from(&sk);
+        let bytes_pk = pk.to_bytes().unwrap();
+
+        let mut trailing = bytes_pk.clone();
+        trailing.push(0);
+        assert_eq!(
+            PublicKey::<Bls12_381>::from_bytes(&trailing),
+            Err(SpsEqSignatureError::TrailingBytes)
+        );
+
+        let mut wrong_version = bytes_pk.clone();
+        wrong_version[0] = VERSION + 1;
+        assert_eq!(
+            PublicKey::<Bls12_381>::from_bytes(&wrong_version),
+            Err(SpsEqSignatureError::UnsupportedVersion)
+        );
+
+        assert_eq!(
+            PublicKey::<Bls12_381>::from_bytes(&bytes_pk[..bytes_pk.len() - 1]),
+            Err(SpsEqSignatureError::TruncatedInput)
+        );
+        assert_eq!(
+            PublicKey::<Bls12_381>::from_bytes(&bytes_pk[..3]),
+            Err(SpsEqSignatureError::TruncatedInput)
+        );
+    }
     #[test]
     fn test_signature() {
         let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
         let pk = PublicKey::from(&sk);
 
-        let message = vec![G1::rand(&mut thread_rng()); 2];
-        let signature = sk.sign(&message, &mut thread_rng());
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+        let signature = sk.sign(&message, &mut thread_rng()).unwrap();
 
         // signature should be valid
         assert!(pk.verify(&message, &signature).is_ok());
 
-        let different_message = vec![G1::rand(&mut thread_rng()); 2];
+        let different_message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
         // signature over a random message should fail
         assert!(pk.verify(&different_message, &signature).is_err())
     }
+
+    #[test]
+    fn test_permuted_message_is_rejected() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+        let signature = sk.sign(&message, rng).unwrap();
+        assert!(pk.verify(&message, &signature).is_ok());
+
+        // With equal secret keys for every slot, any permutation of the message would verify
+        for permutation in [[0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]].iter() {
+            let permuted: Vec<G1> = permutation.iter().map(|&index| message[index]).collect();
+            assert_eq!(
+                pk.verify(&permuted, &signature),
+                Err(SpsEqSignatureError::InvalidSignature)
+            );
+        }
+    }
+
+    /// Samples a point on the curve outside of the prime-order subgroup, which a random point
+    /// is with overwhelming probability
+    fn point_outside_subgroup() -> G1 {
+        let mut bytes = [0u8; 48];
+        loop {
+            thread_rng().fill(&mut bytes[..]);
+            if let Some(point) = G1Affine::from_random_bytes(&bytes) {
+                if !point.is_zero() && !point.is_in_correct_subgroup_assuming_on_curve() {
+                    return point.into_projective();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_verification() {
+        let rng = &mut thread_rng();
+        let sk = SigningKey::<Bls12_381>::new(3, rng);
+        let pk = PublicKey::from(&sk);
+
+        let messages: Vec<Vec<G1>> = (0..10)
+            .map(|_| (0..3).map(|_| G1::rand(rng)).collect())
+            .collect();
+        let mut signatures: Vec<_> = messages
+            .iter()
+            .map(|message| sk.sign(message, rng).unwrap())
+            .collect();
+
+        let batch: Vec<_> = messages
+            .iter()
+            .map(Vec::as_slice)
+            .zip(&signatures)
+            .collect();
+        assert!(pk.batch_verify(&batch, rng).is_ok());
+        assert!(pk.batch_verify(&[], rng).is_ok());
+
+        // Tamper with a few signatures, including a malformed one
+        signatures[2].Z = G1::rand(rng);
+        signatures[7].Yp = G2::rand(rng);
+        signatures[9].Y = G1::zero();
+        let mut different_message = messages[5].clone();
+        different_message[0] = G1::rand(rng);
+
+        let mut batch: Vec<_> = messages
+            .iter()
+            .map(Vec::as_slice)
+            .zip(&signatures)
+            .collect();
+        batch[5].0 = &different_message;
+        assert_eq!(pk.batch_verify(&batch, rng), Err(vec![2, 5, 7, 9]));
+
+        // Points outside the subgroup are rejected even if the equations of the entry hold
+        let mut message_outside_subgroup = messages[3].clone();
+        message_outside_subgroup[1] = point_outside_subgroup();
+        let signature = sk.sign(&message_outside_subgroup, rng).unwrap();
+        assert!(pk.verify(&message_outside_subgroup, &signature).is_ok());
+        batch[3] = (&message_outside_subgroup, &signature);
+        assert_eq!(pk.batch_verify(&batch, rng), Err(vec![2, 3, 5, 7, 9]));
+    }
+
+    #[test]
+    fn test_identity_points_are_rejected() {
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+        let pk = PublicKey::from(&sk);
+
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+        let signature = sk.sign(&message, &mut thread_rng()).unwrap();
+
+        let identity_message = vec![message[0], G1::zero()];
+        assert_eq!(
+            pk.verify(&identity_message, &signature),
+            Err(SpsEqSignatureError::IdentityMessageComponent)
+        );
+
+        // The trivial signature over identity messages satisfies the pairing equations
+        let trivial_signature = SpsEqSignature::<Bls12_381> {
+            Z: G1::zero(),
+            Y: G1::zero(),
+            Yp: G2::zero(),
+        };
+        assert_eq!(
+            pk.verify(&[G1::zero(); 2], &trivial_signature),
+            Err(SpsEqSignatureError::IdentityMessageComponent)
+        );
+        assert_eq!(
+            pk.verify(&message, &trivial_signature),
+            Err(SpsEqSignatureError::IdentitySignatureComponent)
+        );
+
+        let mut identity_y = signature.clone();
+        identity_y.Y = G1::zero();
+        assert_eq!(
+            pk.verify(&message, &identity_y),
+            Err(SpsEqSignatureError::IdentitySignatureComponent)
+        );
+
+        assert_eq!(
+            pk.verify(&[], &signature),
+            Err(SpsEqSignatureError::UnmatchedCapacity)
+        );
+    }
+
+    #[test]
+    fn test_strict_verification() {
+        let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+        let pk = PublicKey::from(&sk);
+
+        let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+        let signature = sk.sign(&message, &mut thread_rng()).unwrap();
+        assert!(pk.verify_strict(&message, &signature).is_ok());
+
+        let outside_subgroup = point_outside_subgroup();
+
+        let mut message_outside_subgroup = message.clone();
+        message_outside_subgroup[1] = outside_subgroup;
+        assert_eq!(
+            pk.verify_strict(&message_outside_subgroup, &signature),
+            Err(SpsEqSignatureError::PointNotInSubgroup)
+        );
+
+        let mut signature_outside_subgroup = signature.clone();
+        signature_outside_subgroup.Z = outside_subgroup;
+        assert_eq!(
+            pk.verify_strict(&message, &signature_outside_subgroup),
+            Err(SpsEqSignatureError::PointNotInSubgroup)
+        );
+    }
 }
@@ -0,0 +1,246 @@
+//! Fuzz-style property tests for the decoding procedures. Decoding is fed random and mutated
+//! byte strings, and must never panic. Every accepted input must be the canonical encoding of
+//! the decoded value.
+
+use sps_eq::sign::*;
+use sps_eq::verify::*;
+use sps_eq::SpsEqSignatureError;
+
+use ark_bls12_381::{Bls12_381, G1Affine, G1Projective as G1, G2Affine};
+use ark_ec::AffineCurve;
+use ark_ff::{UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::{thread_rng, Rng};
+
+const ITERATIONS: usize = 200;
+
+fn random_bytes<R: Rng>(rng: &mut R, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    rng.fill(&mut bytes[..]);
+    bytes
+}
+
+/// Applies a random mutation to `bytes`: flip a bit, overwrite a byte, truncate or extend
+fn mutate<R: Rng>(rng: &mut R, bytes: &[u8]) -> Vec<u8> {
+    let mut mutated = bytes.to_vec();
+    let position = rng.gen::<usize>() % mutated.len();
+    match rng.gen::<u8>() % 4 {
+        0 => mutated[position] ^= 1 << (rng.gen::<u8>() % 8),
+        1 => mutated[position] = rng.gen(),
+        2 => mutated.truncate(position),
+        _ => {
+            let len = 1 + rng.gen::<usize>() % 8;
+            mutated.extend(random_bytes(rng, len))
+        }
+    }
+    mutated
+}
+
+fn compressed(point: &impl CanonicalSerialize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    point.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+struct Encodings {
+    public_keys: Vec<Vec<u8>>,
+    signatures: Vec<Vec<u8>>,
+    signing_keys: Vec<Vec<u8>>,
+}
+
+fn valid_encodings() -> Encodings {
+    let rng = &mut thread_rng();
+    let sk = SigningKey::<Bls12_381>::new(3, rng);
+    let pk = PublicKey::from(&sk);
+    let message: Vec<G1> = (0..3).map(|_| G1::rand(rng)).collect();
+    let signature = sk.sign(&message, rng).unwrap();
+
+    Encodings {
+        public_keys: vec![pk.to_bytes().unwrap(), pk.to_bytes_uncompressed().unwrap()],
+        signatures: vec![
+            signature.to_bytes().unwrap(),
+            signature.to_bytes_uncompressed().unwrap(),
+        ],
+        signing_keys: vec![sk.to_bytes().unwrap()],
+    }
+}
+
+/// Decodes `bytes` with every decoder, checking that accepted inputs are canonical
+fn check_decoders(bytes: &[u8]) {
+    if let Ok(pk) = PublicKey::<Bls12_381>::from_bytes(bytes) {
+        let encoding = match bytes[1] {
+            0 => pk.to_bytes(),
+            _ => pk.to_bytes_uncompressed(),
+        };
+        assert_eq!(encoding.unwrap(), bytes);
+    }
+    if let Ok(signature) = SpsEqSignature::<Bls12_381>::from_bytes(bytes) {
+        let encoding = match bytes[1] {
+            0 => signature.to_bytes(),
+            _ => signature.to_bytes_uncompressed(),
+        };
+        assert_eq!(encoding.unwrap(), bytes);
+    }
+    if let Ok(sk) = SigningKey::<Bls12_381>::from_bytes(bytes) {
+        assert_eq!(sk.to_bytes().unwrap(), bytes);
+    }
+}
+
+#[test]
+fn random_bytes_never_panic() {
+    let rng = &mut thread_rng();
+    for _ in 0..ITERATIONS {
+        let len = rng.gen::<usize>() % 512;
+        let mut bytes = random_bytes(rng, len);
+        // Most random strings stop at the version byte, so half of them get a valid header
+        if rng.gen() && bytes.len() >= 2 {
+            bytes[0] = 1;
+            bytes[1] = rng.gen::<u8>() % 2;
+        }
+        check_decoders(&bytes);
+    }
+}
+
+#[test]
+fn mutated_encodings_are_rejected_or_canonical() {
+    let rng = &mut thread_rng();
+    let encodings = valid_encodings();
+    let all = encodings
+        .public_keys
+        .iter()
+        .chain(&encodings.signatures)
+        .chain(&encodings.signing_keys);
+
+    for encoding in all {
+        for _ in 0..ITERATIONS / 4 {
+            check_decoders(&mutate(rng, encoding));
+        }
+    }
+}
+
+#[test]
+fn truncated_encodings_are_rejected() {
+    let encodings = valid_encodings();
+
+    for encoding in &encodings.public_keys {
+        for len in 0..encoding.len() {
+            assert_eq!(
+                PublicKey::<Bls12_381>::from_bytes(&encoding[..len]),
+                Err(SpsEqSignatureError::TruncatedInput)
+            );
+        }
+    }
+    for encoding in &encodings.signatures {
+        for len in 0..encoding.len() {
+            assert_eq!(
+                SpsEqSignature::<Bls12_381>::from_bytes(&encoding[..len]),
+                Err(SpsEqSignatureError::TruncatedInput)
+            );
+        }
+    }
+    for encoding in &encodings.signing_keys {
+        for len in 0..encoding.len() {
+            assert_eq!(
+                SigningKey::<Bls12_381>::from_bytes(&encoding[..len]),
+                Err(SpsEqSignatureError::TruncatedInput)
+            );
+        }
+    }
+}
+
+#[test]
+fn identity_points_are_rejected() {
+    let encodings = valid_encodings();
+
+    // Replace Y of the compressed signature, located after the header and Z
+    let mut signature = encodings.signatures[0].clone();
+    signature[50..98].copy_from_slice(&compressed(&G1Affine::zero()));
+    assert_eq!(
+        SpsEqSignature::<Bls12_381>::from_bytes(&signature),
+        Err(SpsEqSignatureError::IdentityPoint)
+    );
+
+    // Replace the first key of the compressed public key, located after the length prefix
+    let mut pk = encodings.public_keys[0].clone();
+    pk[6..102].copy_from_slice(&compressed(&G2Affine::zero()));
+    assert_eq!(
+        PublicKey::<Bls12_381>::from_bytes(&pk),
+        Err(SpsEqSignatureError::IdentityPoint)
+    );
+}
+
+#[test]
+fn points_outside_the_subgroup_are_rejected() {
+    let rng = &mut thread_rng();
+    let encodings = valid_encodings();
+
+    // Random points on the curve lie outside of the prime-order subgroup with overwhelming
+    // probability, given the size of the cofactors.
+    let g1_point = loop {
+        if let Some(point) = G1Affine::from_random_bytes(&random_bytes(rng, 48)) {
+            if !point.is_zero() && !point.is_in_correct_subgroup_assuming_on_curve() {
+                break point;
+            }
+        }
+    };
+    let g2_point = loop {
+        if let Some(point) = G2Affine::from_random_bytes(&random_bytes(rng, 96)) {
+            if !point.is_zero() && !point.is_in_correct_subgroup_assuming_on_curve() {
+                break point;
+            }
+        }
+    };
+
+    let mut signature = encodings.signatures[0].clone();
+    signature[2..50].copy_from_slice(&compressed(&g1_point));
+    assert_eq!(
+        SpsEqSignature::<Bls12_381>::from_bytes(&signature),
+        Err(SpsEqSignatureError::PointNotInSubgroup)
+    );
+
+    let mut pk = encodings.public_keys[0].clone();
+    pk[6..102].copy_from_slice(&compressed(&g2_point));
+    assert_eq!(
+        PublicKey::<Bls12_381>::from_bytes(&pk),
+        Err(SpsEqSignatureError::PointNotInSubgroup)
+    );
+}
+
+#[test]
+fn points_off_the_curve_are_rejected() {
+    let rng = &mut thread_rng();
+    let encodings = valid_encodings();
+
+    for _ in 0..ITERATIONS {
+        let bytes = random_bytes(rng, 48);
+        if G1Affine::from_random_bytes(&bytes).is_some() {
+            continue;
+        }
+        let mut signature = encodings.signatures[0].clone();
+        signature[2..50].copy_from_slice(&bytes);
+        assert_eq!(
+            SpsEqSignature::<Bls12_381>::from_bytes(&signature),
+            Err(SpsEqSignatureError::InvalidPointEncoding)
+        );
+    }
+
+    // Changing the y-coordinate of an uncompressed point moves it off the curve
+    let mut signature = encodings.signatures[1].clone();
+    signature[60] ^= 1;
+    assert_eq!(
+        SpsEqSignature::<Bls12_381>::from_bytes(&signature),
+        Err(SpsEqSignatureError::InvalidPointEncoding)
+    );
+}
+
+#[test]
+fn non_canonical_scalars_are_rejected() {
+    let mut sk = valid_encodings().signing_keys[0].clone();
+    for byte in sk[5..37].iter_mut() {
+        *byte = 0xff;
+    }
+    assert_eq!(
+        SigningKey::<Bls12_381>::from_bytes(&sk),
+        Err(SpsEqSignatureError::InvalidScalarEncoding)
+    );
+}
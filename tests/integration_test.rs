@@ -1,5 +1,6 @@
 use sps_eq::sign::*;
 use sps_eq::verify::*;
+use sps_eq::SpsEqSignatureError;
 
 use ark_bls12_381::{Bls12_381, G1Projective as G1};
 use ark_ff::UniformRand;
@@ -10,8 +11,8 @@ fn test_new_repr() {
     let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
     let pk = PublicKey::from(&sk);
 
-    let message = vec![G1::rand(&mut thread_rng()); 2];
-    let signature = sk.sign(&message, &mut thread_rng());
+    let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+    let signature = sk.sign(&message, &mut thread_rng()).unwrap();
 
     assert!(pk.verify(&message, &signature).is_ok());
 
@@ -21,3 +22,50 @@ fn test_new_repr() {
 
     assert!(pk.verify(&new_repr_message, &new_repr_signature).is_ok());
 }
+
+#[test]
+fn test_swapped_message_components_are_rejected() {
+    let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+    let pk = PublicKey::from(&sk);
+
+    let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+    let signature = sk.sign(&message, &mut thread_rng()).unwrap();
+    let (new_repr_signature, new_repr_message) =
+        signature.generate_new_repr(&message, &mut thread_rng());
+
+    let swapped_message = vec![new_repr_message[1], new_repr_message[0]];
+    assert!(pk.verify(&swapped_message, &new_repr_signature).is_err());
+}
+
+#[test]
+fn test_checked_new_repr() {
+    let sk = SigningKey::<Bls12_381>::new(2, &mut thread_rng());
+    let pk = PublicKey::from(&sk);
+
+    let message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+    let mut signature = sk.sign(&message, &mut thread_rng()).unwrap();
+
+    let (new_repr_signature, new_repr_message) = signature
+        .clone()
+        .generate_new_repr_checked(&message, &pk, &mut thread_rng())
+        .unwrap();
+    assert!(pk.verify(&new_repr_message, &new_repr_signature).is_ok());
+
+    let new_repr_message = signature
+        .change_repr_checked(&message, &pk, &mut thread_rng())
+        .unwrap();
+    assert!(pk.verify(&new_repr_message, &signature).is_ok());
+
+    // A signature that does not verify is not randomised
+    let different_message = vec![G1::rand(&mut thread_rng()), G1::rand(&mut thread_rng())];
+    let unchanged = signature.clone();
+    assert_eq!(
+        signature.change_repr_checked(&different_message, &pk, &mut thread_rng()),
+        Err(SpsEqSignatureError::InvalidSignature)
+    );
+    assert_eq!(signature, unchanged);
+    assert_eq!(
+        signature.generate_new_repr_checked(&different_message, &pk, &mut thread_rng()),
+        Err(SpsEqSignatureError::InvalidSignature)
+    );
+}